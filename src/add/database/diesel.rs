@@ -1,108 +1,162 @@
+use crate::files;
 use crate::{
-    add::{add_dependencies, write_config, AddFeature, Dependency, FileEditor},
-    config::DatabaseType,
+    add::{add_dependencies, create_file, write_config, AddFeature, Dependency, RustFile},
+    config::{ApiFramework, DatabaseType, UuidExtension},
 };
 use anyhow::Result;
 use askama::Template;
 use std::path::Path;
 
-use super::{update_config_files, update_routes};
+use super::{update_api_context, update_config_files, update_routes, update_startup};
+
+/// diesel-async needs 0.5 for its sqlite support, which in turn needs diesel 2.2
+pub const DIESEL_VERSION: &str = "2.2.0";
+pub const DIESEL_ASYNC_VERSION: &str = "0.5.0";
+
+const CONNECTION: &str =
+    "db: settings.database.get_connection_pool().context(\"Failed to connect to database\")?";
 
 #[derive(Template)]
 #[template(path = "./add/database/diesel.rs.templ", escape = "html")]
 pub struct DieselConfigTemplate {
     pub database: DatabaseType,
+    pub api_framework: ApiFramework,
+    pub uuid_extension: UuidExtension,
 }
 
 impl DieselConfigTemplate {
-    pub fn new(database: DatabaseType) -> Self {
-        Self { database }
+    pub fn new(
+        database: DatabaseType,
+        api_framework: ApiFramework,
+        uuid_extension: UuidExtension,
+    ) -> Self {
+        Self {
+            database,
+            api_framework,
+            uuid_extension,
+        }
     }
 
     fn dependencies(&self) -> Vec<Dependency> {
         let db = match self.database {
             DatabaseType::PostgreSQL => "postgres",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::Sqlite => {
+                // diesel-async only supports sqlite through the sync connection wrapper
+                return vec![
+                    ("diesel", DIESEL_VERSION, Some(vec!["sqlite"])),
+                    (
+                        "diesel-async",
+                        DIESEL_ASYNC_VERSION,
+                        Some(vec!["sqlite", "deadpool"]),
+                    ),
+                ];
+            }
         };
 
         vec![
-            ("diesel", "2.1.0", Some(vec![db])),
-            ("diesel-async", "0.4.1", Some(vec![db, "deadpool"])),
+            ("diesel", DIESEL_VERSION, Some(vec![db])),
+            (
+                "diesel-async",
+                DIESEL_ASYNC_VERSION,
+                Some(vec![db, "deadpool"]),
+            ),
             ("secrecy", "0.8.0", Some(vec!["serde"])),
             ("serde-aux", "4.1.2", None),
         ]
     }
 
-    fn update_config(&self, path: &Path) -> Result<()> {
-        let add_use = |lines: &mut Vec<&str>| {
-            lines.insert(3, "use database::{DatabaseSettings, PgPool};");
-            lines.insert(0, "mod database;");
-        };
-
-        let update_settings = |lines: &mut Vec<&str>, i: usize| {
-            lines.insert(i + 1, "    pub database: DatabaseSettings,");
-        };
-
-        let update_context = |lines: &mut Vec<&str>, i: usize| {
-            lines.insert(i + 1, "    pub db: PgPool,");
-        };
-
-        let add_context = |lines: &mut Vec<&str>, has_been_called: Vec<bool>| {
-            if has_been_called[has_been_called.len() - 1] {
-                return;
-            }
-            lines.push("#[derive(Clone)]");
-            lines.push("pub struct ApiContext {");
-            lines.push("    pub db: PgPool,");
-            lines.push("}");
-        };
+    /// Migration that diesel runs before all others, the same as the one of `diesel setup`
+    fn add_initial_setup(&self, path: &Path) -> Result<()> {
+        let migration_path = path.join("migrations/00000000000000_diesel_initial_setup");
+        files::create_dir_all(&migration_path)?;
+        if self.database != DatabaseType::PostgreSQL {
+            // MySQL uses ON UPDATE CURRENT_TIMESTAMP and SQLite a trigger per table
+            return Ok(());
+        }
+
+        let mut up = String::from(
+            r#"-- This file was automatically created by Diesel to setup helper functions
+-- and other internal bookkeeping. This file is safe to edit, any future
+-- changes will be added to existing projects as new migrations.
+
+"#,
+        );
+        let mut down = up.clone();
+        if let (Some(create), Some(drop)) = (
+            self.uuid_extension.create_statement(),
+            self.uuid_extension.drop_statement(),
+        ) {
+            up.push_str(&format!("-- add extension for uuid ids\n{}\n\n", create));
+            down.push_str(&format!("-- remove extension for uuid ids\n{}\n\n", drop));
+        }
+
+        up.push_str(
+            r#"-- Sets up a trigger for the given table to automatically set a column called
+-- `updated_at` whenever the row is modified (unless `updated_at` was included
+-- in the modified columns)
+--
+-- # Example
+--
+-- ```sql
+-- CREATE TABLE users (id SERIAL PRIMARY KEY, updated_at TIMESTAMP NOT NULL DEFAULT NOW());
+--
+-- SELECT diesel_manage_updated_at('users');
+-- ```
+CREATE OR REPLACE FUNCTION diesel_manage_updated_at(_tbl regclass) RETURNS VOID AS $$
+BEGIN
+    EXECUTE format('CREATE TRIGGER set_updated_at BEFORE UPDATE ON %s
+                    FOR EACH ROW EXECUTE PROCEDURE diesel_set_updated_at()', _tbl);
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION diesel_set_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF (
+        NEW IS DISTINCT FROM OLD AND
+        NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at
+    ) THEN
+        NEW.updated_at := current_timestamp;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+"#,
+        );
+        down.push_str(
+            r#"DROP FUNCTION IF EXISTS diesel_manage_updated_at(_tbl regclass);
+DROP FUNCTION IF EXISTS diesel_set_updated_at();
+"#,
+        );
 
-        FileEditor::new(&path.join("src/config/mod.rs"))
-            .before_change(add_use)
-            .add_change(update_settings, vec!["pub struct Settings {"])
-            .add_change(update_context, vec!["pub struct ApiContext {"])
-            .after_change(add_context)
-            .edit_file()?;
+        create_file(&migration_path.join("up.sql"), &up)?;
+        create_file(&migration_path.join("down.sql"), &down)?;
         Ok(())
     }
 
-    fn update_startup(&self, path: &Path) -> Result<()> {
-        let startup_path = path.join("./src/startup.rs");
-
-        let update_context = |lines: &mut Vec<&str>, i: usize| {
-            lines.insert(i + 1, "    db: settings.database.get_connection_pool().context(\"Failed to connect to database\")?,");
-        };
-
-        FileEditor::new(&startup_path)
-            .add_change(update_context, vec!["let api_context = ApiContext {"])
-            .after_change(|lines, has_been_called| {
-                if has_been_called[0] {
-                    return;
-                }
-                lines.insert(0, "use crate::config::ApiContext;");
-                let pos = lines
-                    .iter()
-                    .position(|line| line.contains("pub async fn build"));
-                if let Some(pos) = pos {
-                    lines.insert(pos + 1, "    let api_context = ApiContext {");
-                    lines.insert(
-                        pos + 2,
-                        "         db: settings.database.get_connection_pool().context(\"Failed to connect to database\")?,",
-                    );
-                    lines.insert(pos + 3, "    };");
-                }
-
-                let pos = lines
-                    .iter()
-                    .position(|line| line.contains("nest(\"/api\", api_routes())"));
-                if let Some(pos) = pos {
-                    lines.insert(pos + 1, "       .with_state(api_context.clone())");
-                }
-            })
-            .edit_file()?;
-
-        update_config_files(path)?;
-
-        Ok(())
+    /// diesel.toml and the schema.rs `generate` adds its tables to
+    fn add_schema(&self, path: &Path) -> Result<()> {
+        create_file(
+            &path.join("diesel.toml"),
+            r#"# For documentation on how to configure this file,
+# see https://diesel.rs/guides/configuring-diesel-cli
+
+[print_schema]
+file = "src/schema.rs"
+custom_type_derives = ["diesel::query_builder::QueryId"]
+
+[migrations_directory]
+dir = "migrations"
+"#,
+        )?;
+        create_file(
+            &path.join("src/schema.rs"),
+            "// @generated automatically by Diesel CLI.\n",
+        )?;
+
+        let mut lib = RustFile::open(&path.join("src/lib.rs"))?;
+        lib.add_mod("pub mod schema;")?;
+        lib.save()
     }
 }
 
@@ -110,9 +164,14 @@ impl AddFeature for DieselConfigTemplate {
     fn add_feature(&self, path: &Path) -> Result<()> {
         add_dependencies(path, self.dependencies())?;
         write_config(&path.join("src/config/database.rs"), self)?;
-        self.update_config(path)?;
-        self.update_startup(path)?;
-        update_routes(path)?;
+        update_api_context(path)?;
+        update_startup(path, &self.api_framework, CONNECTION)?;
+        update_config_files(path, &self.database)?;
+        self.add_initial_setup(path)?;
+        self.add_schema(path)?;
+        if self.api_framework == ApiFramework::Axum {
+            update_routes(path)?;
+        }
         Ok(())
     }
 }
@@ -124,15 +183,47 @@ mod test {
     #[test]
     fn test_dependencies() {
         let database = DatabaseType::PostgreSQL;
-        let template = DieselConfigTemplate::new(database);
+        let template =
+            DieselConfigTemplate::new(database, ApiFramework::Axum, UuidExtension::default());
         let db = "postgres";
         assert_eq!(
             template.dependencies(),
             vec![
-                ("diesel", "2.1.0", Some(vec![db])),
-                ("diesel_async", "0.4.1", Some(vec![db, "deadpool"])),
+                ("diesel", "2.2.0", Some(vec![db])),
+                ("diesel-async", "0.5.0", Some(vec![db, "deadpool"])),
                 ("secrecy", "0.8.0", Some(vec!["serde"])),
-                ("serde_aux", "4.1.2", None),
+                ("serde-aux", "4.1.2", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mysql_dependencies() {
+        let template = DieselConfigTemplate::new(
+            DatabaseType::MySQL,
+            ApiFramework::Axum,
+            UuidExtension::default(),
+        );
+        let dependencies = template.dependencies();
+        assert_eq!(dependencies[0], ("diesel", "2.2.0", Some(vec!["mysql"])));
+        assert_eq!(
+            dependencies[1],
+            ("diesel-async", "0.5.0", Some(vec!["mysql", "deadpool"]))
+        );
+    }
+
+    #[test]
+    fn test_sqlite_dependencies() {
+        let template = DieselConfigTemplate::new(
+            DatabaseType::Sqlite,
+            ApiFramework::Axum,
+            UuidExtension::default(),
+        );
+        assert_eq!(
+            template.dependencies(),
+            vec![
+                ("diesel", "2.2.0", Some(vec!["sqlite"])),
+                ("diesel-async", "0.5.0", Some(vec!["sqlite", "deadpool"])),
             ]
         );
     }
@@ -142,14 +233,14 @@ mod test {
     #[test]
     fn test_write_dependencies() {
         let database = DatabaseType::Postgres;
-        let template = DieselConfigTemplate::new(database);
+        let template = DieselConfigTemplate::new(database, ApiFramework::Axum, UuidExtension::default());
         template.write_dependencies().unwrap();
     }
 
     #[test]
     fn test_render_sqlx() {
         let database = DatabaseType::Postgres;
-        let template = DieselConfigTemplate::new(database);
+        let template = DieselConfigTemplate::new(database, ApiFramework::Axum, UuidExtension::default());
         template.render_sqlx().unwrap();
     }
     */
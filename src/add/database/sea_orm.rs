@@ -0,0 +1,139 @@
+use crate::files;
+use crate::{
+    add::{add_dependencies, create_file, write_config, AddFeature, Dependency},
+    config::{ApiFramework, DatabaseType},
+};
+use anyhow::Result;
+use askama::Template;
+use std::path::Path;
+
+use super::{update_api_context, update_config_files, update_routes, update_startup};
+
+const CONNECTION: &str =
+    "db: settings.database.get_connection_pool().await.context(\"Failed to connect to database\")?";
+
+#[derive(Template)]
+#[template(path = "./add/database/sea_orm.rs.templ", escape = "html")]
+pub struct SeaOrmConfigTemplate {
+    pub database: DatabaseType,
+    pub api_framework: ApiFramework,
+}
+
+impl SeaOrmConfigTemplate {
+    pub fn new(database: DatabaseType, api_framework: ApiFramework) -> Self {
+        Self {
+            database,
+            api_framework,
+        }
+    }
+
+    fn sqlx_feature(&self) -> &'static str {
+        match self.database {
+            DatabaseType::PostgreSQL => "sqlx-postgres",
+            DatabaseType::MySQL => "sqlx-mysql",
+            DatabaseType::Sqlite => "sqlx-sqlite",
+        }
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        vec![
+            (
+                "sea-orm",
+                "0.12.15",
+                Some(vec!["runtime-tokio-rustls", "macros", self.sqlx_feature()]),
+            ),
+            ("secrecy", "0.8.0", Some(vec!["serde"])),
+            ("serde-aux", "4.1.2", None),
+        ]
+    }
+
+    /// Creates the `migration` crate that `generate -o sql` adds its migrations to
+    fn add_migration_crate(&self, path: &Path) -> Result<()> {
+        let migration_path = path.join("migration");
+        files::create_dir_all(migration_path.join("src"))?;
+
+        create_file(
+            &migration_path.join("Cargo.toml"),
+            &format!(
+                r#"[package]
+name = "migration"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[lib]
+name = "migration"
+path = "src/lib.rs"
+
+[dependencies]
+tokio = {{ version = "1", features = ["macros", "rt-multi-thread"] }}
+
+[dependencies.sea-orm-migration]
+version = "0.12.15"
+features = ["runtime-tokio-rustls", "{}"]
+"#,
+                self.sqlx_feature()
+            ),
+        )?;
+
+        create_file(
+            &migration_path.join("src/lib.rs"),
+            r#"pub use sea_orm_migration::prelude::*;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![]
+    }
+}
+"#,
+        )?;
+
+        create_file(
+            &migration_path.join("src/main.rs"),
+            r#"use sea_orm_migration::prelude::*;
+
+#[tokio::main]
+async fn main() {
+    cli::run_cli(migration::Migrator).await;
+}
+"#,
+        )?;
+        Ok(())
+    }
+}
+
+impl AddFeature for SeaOrmConfigTemplate {
+    fn add_feature(&self, path: &Path) -> Result<()> {
+        add_dependencies(path, self.dependencies())?;
+        write_config(&path.join("src/config/database.rs"), self)?;
+        update_api_context(path)?;
+        update_startup(path, &self.api_framework, CONNECTION)?;
+        update_config_files(path, &self.database)?;
+        self.add_migration_crate(path)?;
+        if self.api_framework == ApiFramework::Axum {
+            update_routes(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dependencies() {
+        let template = SeaOrmConfigTemplate::new(DatabaseType::Sqlite, ApiFramework::Axum);
+        assert_eq!(
+            template.dependencies()[0],
+            (
+                "sea-orm",
+                "0.12.15",
+                Some(vec!["runtime-tokio-rustls", "macros", "sqlx-sqlite"])
+            )
+        );
+    }
+}
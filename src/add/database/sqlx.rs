@@ -1,27 +1,42 @@
+use crate::files;
 use crate::{
-    add::{add_dependencies, write_config, AddFeature, Dependency, FileEditor},
-    config::DatabaseType,
+    add::{add_dependencies, create_file, write_config, AddFeature, Dependency},
+    config::{ApiFramework, DatabaseType, UuidExtension},
 };
 use anyhow::Result;
 use askama::Template;
-use std::{fs, path::Path};
+use std::path::Path;
 
-use super::{update_config_files, update_routes};
+use super::{update_api_context, update_config_files, update_routes, update_startup};
+
+const CONNECTION: &str = "db: settings.database.get_connection_pool()";
 
 #[derive(Template)]
 #[template(path = "./add/database/sqlx.rs.templ", escape = "html")]
 pub struct SqlxConfigTemplate {
     pub database: DatabaseType,
+    pub api_framework: ApiFramework,
+    pub uuid_extension: UuidExtension,
 }
 
 impl SqlxConfigTemplate {
-    pub fn new(database: DatabaseType) -> Self {
-        Self { database }
+    pub fn new(
+        database: DatabaseType,
+        api_framework: ApiFramework,
+        uuid_extension: UuidExtension,
+    ) -> Self {
+        Self {
+            database,
+            api_framework,
+            uuid_extension,
+        }
     }
 
     fn dependencies(&self) -> Vec<Dependency> {
         let db = match self.database {
             DatabaseType::PostgreSQL => "postgres",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::Sqlite => "sqlite",
         };
         vec![
             (
@@ -34,84 +49,24 @@ impl SqlxConfigTemplate {
         ]
     }
 
-    fn update_config(&self, path: &Path) -> Result<()> {
-        let add_use = |lines: &mut Vec<&str>| {
-            lines.insert(3, "use database::DatabaseSettings;");
-            lines.insert(3, "use sqlx::PgPool;");
-            lines.insert(0, "mod database;");
-        };
-
-        let update_settings = |lines: &mut Vec<&str>, i: usize| {
-            lines.insert(i + 1, "    pub database: DatabaseSettings,");
-        };
-
-        let update_context = |lines: &mut Vec<&str>, i: usize| {
-            lines.insert(i + 1, "    pub db: PgPool,");
-        };
-
-        let add_context = |lines: &mut Vec<&str>, has_been_called: Vec<bool>| {
-            if has_been_called[has_been_called.len() - 1] {
-                return;
-            }
-            lines.push("#[derive(Clone)]");
-            lines.push("pub struct ApiContext {");
-            lines.push("    pub db: PgPool,");
-            lines.push("}");
-        };
-
-        FileEditor::new(&path.join("src/config/mod.rs"))
-            .before_change(add_use)
-            .add_change(update_settings, vec!["pub struct Settings {"])
-            .add_change(update_context, vec!["pub struct ApiContext {"])
-            .after_change(add_context)
-            .edit_file()?;
-
-        Ok(())
-    }
-
-    fn update_startup(&self, path: &Path) -> Result<()> {
-        let startup_path = path.join("./src/startup.rs");
-
-        let update_context = |lines: &mut Vec<&str>, i: usize| {
-            lines.insert(i + 1, "    db: settings.database.get_connection_pool(),");
-        };
-
-        FileEditor::new(&startup_path)
-            .add_change(update_context, vec!["let api_context = ApiContext {"])
-            .after_change(|lines, has_been_called| {
-                if has_been_called[0] {
-                    return;
-                }
-                lines.insert(0, "use crate::config::ApiContext;");
-                let pos = lines
-                    .iter()
-                    .position(|line| line.contains("pub async fn build"));
-                if let Some(pos) = pos {
-                    lines.insert(pos + 1, "    let api_context = ApiContext {");
-                    lines.insert(
-                        pos + 2,
-                        "         db: settings.database.get_connection_pool(),",
-                    );
-                    lines.insert(pos + 3, "    };");
-                }
-
-                let pos = lines
-                    .iter()
-                    .position(|line| line.contains("nest(\"/api\", api_routes())"));
-                if let Some(pos) = pos {
-                    lines.insert(pos + 1, "       .with_state(api_context.clone())");
-                }
-            })
-            .edit_file()?;
-
-        update_config_files(path)?;
-
-        Ok(())
-    }
-
     fn add_updated_at(&self, path: &Path) -> Result<()> {
-        fs::create_dir(path.join("migrations"))?;
-        FileEditor::new(&path.join("migrations/20210101000000_initial_setup.up.sql")).create_file(
+        files::create_dir_all(path.join("migrations"))?;
+        if self.database != DatabaseType::PostgreSQL {
+            // MySQL uses ON UPDATE CURRENT_TIMESTAMP and SQLite a trigger per table
+            return Ok(());
+        }
+
+        let mut up = String::new();
+        let mut down = String::new();
+        if let (Some(create), Some(drop)) = (
+            self.uuid_extension.create_statement(),
+            self.uuid_extension.drop_statement(),
+        ) {
+            up.push_str(&format!("\n-- add extension for uuid ids\n{}\n", create));
+            down.push_str(&format!("\n-- remove extension for uuid ids\n{}\n", drop));
+        }
+
+        up.push_str(
             r#"
 -- add function for updated_at
 
@@ -135,17 +90,25 @@ END;
 $$ LANGUAGE plpgsql;
 
 "#,
-        )?;
-        FileEditor::new(&path.join("migrations/20210101000000_initial_setup.down.sql"))
-            .create_file(
-                r#"
+        );
+        down.push_str(
+            r#"
 -- remove function for updated_at
 DROP FUNCTION IF EXISTS manage_updated_at(_tbl regclass);
 
 DROP FUNCTION IF EXISTS set_updated_at();
 
 "#,
-            )?;
+        );
+
+        create_file(
+            &path.join("migrations/20210101000000_initial_setup.up.sql"),
+            &up,
+        )?;
+        create_file(
+            &path.join("migrations/20210101000000_initial_setup.down.sql"),
+            &down,
+        )?;
         Ok(())
     }
 }
@@ -154,10 +117,13 @@ impl AddFeature for SqlxConfigTemplate {
     fn add_feature(&self, path: &Path) -> Result<()> {
         add_dependencies(path, self.dependencies())?;
         write_config(&path.join("src/config/database.rs"), self)?;
-        self.update_config(path)?;
-        self.update_startup(path)?;
+        update_api_context(path)?;
+        update_startup(path, &self.api_framework, CONNECTION)?;
+        update_config_files(path, &self.database)?;
         self.add_updated_at(path)?;
-        update_routes(path)?;
+        if self.api_framework == ApiFramework::Axum {
+            update_routes(path)?;
+        }
         Ok(())
     }
 }
@@ -169,7 +135,8 @@ mod test {
     #[test]
     fn test_dependencies() {
         let database = DatabaseType::PostgreSQL;
-        let template = SqlxConfigTemplate::new(database);
+        let template =
+            SqlxConfigTemplate::new(database, ApiFramework::Axum, UuidExtension::default());
         assert_eq!(
             template.dependencies(),
             vec![
@@ -180,7 +147,6 @@ mod test {
                         "runtime-tokio-rustls",
                         "macros",
                         "migrate",
-                        "offline",
                         "postgres"
                     ])
                 ),
@@ -190,19 +156,36 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_mysql_dependencies() {
+        let template = SqlxConfigTemplate::new(
+            DatabaseType::MySQL,
+            ApiFramework::Axum,
+            UuidExtension::default(),
+        );
+        assert_eq!(
+            template.dependencies()[0],
+            (
+                "sqlx",
+                "0.7.4",
+                Some(vec!["runtime-tokio-rustls", "macros", "migrate", "mysql"])
+            )
+        );
+    }
+
     /*
      * Should be done in integration tests
     #[test]
     fn test_write_dependencies() {
         let database = DatabaseType::Postgres;
-        let template = SqlxConfigTemplate::new(database);
+        let template = SqlxConfigTemplate::new(database, ApiFramework::Axum, UuidExtension::default());
         template.write_dependencies().unwrap();
     }
 
     #[test]
     fn test_render_sqlx() {
         let database = DatabaseType::Postgres;
-        let template = SqlxConfigTemplate::new(database);
+        let template = SqlxConfigTemplate::new(database, ApiFramework::Axum, UuidExtension::default());
         template.render_sqlx().unwrap();
     }
     */
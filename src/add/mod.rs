@@ -1,14 +1,20 @@
 pub mod database;
+mod rust_file;
 
-use crate::config::{self, DatabaseDriver};
+use crate::config::{self, Config, DatabaseDriver};
+use crate::files;
 use anyhow::{Context, Result};
 use askama::Template;
 use clap::Subcommand;
-use database::{diesel::DieselConfigTemplate, sqlx::SqlxConfigTemplate};
-use std::{fs, path::Path};
-use toml_edit::{value, Array, DocumentMut};
+use console::style;
+use database::{
+    diesel::DieselConfigTemplate, sea_orm::SeaOrmConfigTemplate, sqlx::SqlxConfigTemplate,
+};
+use std::path::Path;
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item};
+
+pub use self::rust_file::{FieldChange, RustFile};
 
-//TODO: Update schmiede.toml
 #[derive(Subcommand, Debug)]
 pub enum Features {
     Database(config::Database),
@@ -18,102 +24,49 @@ pub trait AddFeature {
     fn add_feature(&self, path: &Path) -> Result<()>;
 }
 
-pub fn add_addon(feature: Features, update_config: bool) -> Result<()> {
+pub fn add_addon(feature: Features, path: &Path, config: &mut Config) -> Result<()> {
     match feature {
         Features::Database(db) => {
-            let template: Box<dyn AddFeature> = match db.database_driver {
-                DatabaseDriver::Sqlx => Box::new(SqlxConfigTemplate::new(db.database_type.clone())),
-                DatabaseDriver::Diesel => {
-                    Box::new(DieselConfigTemplate::new(db.database_type.clone()))
+            if let Some(installed) = &config.database {
+                if installed.database_driver != db.database_driver
+                    || installed.database_type != db.database_type
+                {
+                    anyhow::bail!(
+                        "The project already uses {:?} with {:?}, switching the database is not supported",
+                        installed.database_driver,
+                        installed.database_type
+                    );
                 }
-            };
-
-            template.add_feature(Path::new("."))?;
-            if update_config {
-                let mut config = config::Config::from_file()?;
-                config.database = Some(db);
-                config.update_config()?;
+                println!("{}", style("The database is already installed").dim());
+                return Ok(());
             }
-        }
-    }
-    Ok(())
-}
-
-type ProcessFunction = fn(&mut Vec<&str>, usize) -> ();
-type BeforeFunction = fn(&mut Vec<&str>) -> ();
-type AfterFunction = fn(&mut Vec<&str>, Vec<bool>) -> ();
-
-struct FileEditor<'a> {
-    file_path: &'a Path,
-    changes: Vec<(ProcessFunction, Vec<&'a str>)>,
-    before_changes: Option<BeforeFunction>,
-    after_changes: Option<AfterFunction>,
-}
-
-impl<'a> FileEditor<'a> {
-    pub fn new(file_path: &'a Path) -> Self {
-        Self {
-            file_path,
-            changes: Vec::new(),
-            before_changes: None,
-            after_changes: None,
-        }
-    }
 
-    pub fn add_change(&mut self, change: ProcessFunction, conditions: Vec<&'a str>) -> &mut Self {
-        self.changes.push((change, conditions));
-        self
-    }
-
-    pub fn before_change(&mut self, change: BeforeFunction) -> &mut Self {
-        self.before_changes = Some(change);
-        self
-    }
-
-    pub fn after_change(&mut self, change: AfterFunction) -> &mut Self {
-        self.after_changes = Some(change);
-        self
-    }
-
-    pub fn edit_file(&self) -> Result<()> {
-        let file = fs::read_to_string(self.file_path)
-            .context(format!("Failed to read file: {}", self.file_path.display()))?;
-        let mut lines = file.lines().collect::<Vec<_>>();
-
-        if let Some(change) = self.before_changes {
-            change(&mut lines);
-        }
-
-        let mut has_been_called = vec![false; self.changes.len()];
+            let template: Box<dyn AddFeature> = match db.database_driver {
+                DatabaseDriver::Sqlx => Box::new(SqlxConfigTemplate::new(
+                    db.database_type.clone(),
+                    config.api_framework.clone(),
+                    db.uuid_extension.clone(),
+                )),
+                DatabaseDriver::Diesel => Box::new(DieselConfigTemplate::new(
+                    db.database_type.clone(),
+                    config.api_framework.clone(),
+                    db.uuid_extension.clone(),
+                )),
+                DatabaseDriver::SeaOrm => Box::new(SeaOrmConfigTemplate::new(
+                    db.database_type.clone(),
+                    config.api_framework.clone(),
+                )),
+            };
 
-        'lines: for (i, line) in lines.clone().iter().enumerate() {
-            for (j, (change, conditions)) in self.changes.iter().enumerate() {
-                if !has_been_called[j] && conditions.iter().all(|c| line.contains(c)) {
-                    change(&mut lines, i);
-                    has_been_called[j] = true;
-                    continue 'lines;
-                }
+            let changes = files::changes();
+            template.add_feature(path)?;
+            if files::changes() == changes {
+                println!("{}", style("The database is already installed").dim());
             }
+            config.database = Some(db);
         }
-
-        if let Some(change) = self.after_changes {
-            change(&mut lines, has_been_called);
-        }
-
-        let updated_file = lines.join("\n");
-        fs::write(self.file_path, updated_file).context(format!(
-            "Failed to update file: {}",
-            self.file_path.display()
-        ))?;
-        Ok(())
-    }
-
-    pub fn create_file(&self, content: &str) -> Result<()> {
-        fs::write(self.file_path, content).context(format!(
-            "Failed to create file: {}",
-            self.file_path.display()
-        ))
     }
+    Ok(())
 }
 
 pub type Dependency = (&'static str, &'static str, Option<Vec<&'static str>>);
@@ -121,7 +74,7 @@ pub type Dependency = (&'static str, &'static str, Option<Vec<&'static str>>);
 pub fn add_dependencies(path: &Path, dependencies: Vec<Dependency>) -> Result<()> {
     let toml_path = path.join("Cargo.toml");
     let toml_contents =
-        fs::read_to_string(&toml_path).with_context(|| "Failed to read Cargo.toml")?;
+        files::read_to_string(&toml_path).with_context(|| "Failed to read Cargo.toml")?;
 
     let mut manifest = toml_contents
         .parse::<DocumentMut>()
@@ -132,7 +85,10 @@ pub fn add_dependencies(path: &Path, dependencies: Vec<Dependency>) -> Result<()
         .ok_or(anyhow::anyhow!("Failed to get dependencies"))?;
 
     for (name, version, features) in dependencies {
-        if let Some(features) = features {
+        // Existing dependencies keep their version and only get the missing features
+        if deps.get(name).is_some() {
+            merge_features(&mut deps[name], features.unwrap_or_default())?;
+        } else if let Some(features) = features {
             deps[name]["version"] = value(version);
             let mut array = Array::default();
             for feature in features {
@@ -146,15 +102,105 @@ pub fn add_dependencies(path: &Path, dependencies: Vec<Dependency>) -> Result<()
     }
 
     let updated_toml = manifest.to_string();
-    fs::write(toml_path, updated_toml).with_context(|| "Failed to write Cargo.toml")?;
+    files::write(toml_path, updated_toml).with_context(|| "Failed to write Cargo.toml")?;
+    Ok(())
+}
+
+fn merge_features(dependency: &mut Item, features: Vec<&str>) -> Result<()> {
+    if features.is_empty() {
+        return Ok(());
+    }
+    if let Some(version) = dependency.as_str() {
+        let mut table = InlineTable::new();
+        table.insert("version", version.into());
+        *dependency = value(table);
+    }
+
+    let dependency = dependency
+        .as_table_like_mut()
+        .ok_or(anyhow::anyhow!("Failed to get dependency"))?;
+    let mut array = dependency
+        .get("features")
+        .and_then(Item::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for feature in features {
+        if !array
+            .iter()
+            .any(|existing| existing.as_str() == Some(feature))
+        {
+            array.push(feature);
+        }
+    }
+    dependency.insert("features", value(array));
     Ok(())
 }
 
+/// Renders the template to a new file, an existing file is kept as it may have been edited
 pub fn write_config<T: Template>(path: &Path, template: &T) -> Result<()> {
+    if files::exists(path) {
+        return Ok(());
+    }
     let rendered = template
         .render()
         .with_context(|| "Failed to render template")?;
-    fs::write(path, rendered)
+    files::write(path, rendered)
         .with_context(|| format!("Failed to write file: {}", path.display()))?;
     Ok(())
 }
+
+/// Creates the file unless it exists, like `write_config` for files without a template
+pub fn create_file(path: &Path, contents: &str) -> Result<()> {
+    if files::exists(path) {
+        return Ok(());
+    }
+    files::write(path, contents)
+        .with_context(|| format!("Failed to write file: {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Database, DatabaseType};
+
+    fn diesel_config() -> Config {
+        Config {
+            database: Some(Database::new(
+                DatabaseType::PostgreSQL,
+                DatabaseDriver::Diesel,
+            )),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_add_installed_database() {
+        // The project is empty, adding anything to it would fail
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = diesel_config();
+        let database = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::Diesel);
+
+        add_addon(Features::Database(database), dir.path(), &mut config).unwrap();
+        assert_eq!(dir.path().read_dir().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_add_other_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = diesel_config();
+
+        let sqlx = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::Sqlx);
+        let err = add_addon(Features::Database(sqlx), dir.path(), &mut config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("already uses Diesel with PostgreSQL"));
+
+        let sqlite = Database::new(DatabaseType::Sqlite, DatabaseDriver::Diesel);
+        assert!(add_addon(Features::Database(sqlite), dir.path(), &mut config).is_err());
+        assert_eq!(
+            config.database.unwrap().database_driver,
+            DatabaseDriver::Diesel
+        );
+    }
+}
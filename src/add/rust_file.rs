@@ -0,0 +1,1377 @@
+use crate::files;
+use anyhow::{Context, Result};
+use proc_macro2::{extra::DelimSpan, LineColumn, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::path::{Path, PathBuf};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    braced, parenthesized, token, Attribute, Block, Expr, ExprMethodCall, ExprStruct, Field,
+    FieldValue, File, Ident, ImplItem, ImplItemFn, Item, ItemFn, ItemMacro, ItemMod, ItemStruct,
+    ItemUse, Macro, Meta, Path as SynPath, ReturnType, Stmt, Token, Type, Visibility,
+};
+
+/// Longest method chain that is kept on one line, like rustfmt does
+const CHAIN_WIDTH: usize = 60;
+
+/// Change to a field of a struct or a column of a `diesel::table!`
+#[derive(Debug, PartialEq)]
+pub enum FieldChange {
+    /// Field like `views: i32` or column like `views -> Int4`, added in front of the timestamps
+    Add(String),
+    Remove(String),
+    Rename(String, String),
+    /// Name and new definition of a field
+    Replace(String, String),
+}
+
+/// Rust file that is edited through its syntax tree.
+/// Changes are inserted as text at the positions of the parsed items, so the formatting and
+/// comments of the file are kept. Changes that are already in the file are left out.
+pub struct RustFile {
+    path: PathBuf,
+    source: String,
+}
+
+impl RustFile {
+    pub fn open(path: &Path) -> Result<Self> {
+        let source = files::read_to_string(path)
+            .context(format!("Failed to read file: {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// File that doesn't exist yet, it is created on `save`
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            source: String::new(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        files::write(&self.path, &self.source)
+            .context(format!("Failed to update file: {}", self.path.display()))
+    }
+
+    pub fn has_function(&self, name: &str) -> Result<bool> {
+        Ok(find_function(&self.parse()?, name).is_some())
+    }
+
+    /// Names of the tables declared with `diesel::table!`
+    pub fn tables(&self) -> Result<Vec<String>> {
+        Ok(find_tables(&self.parse()?)
+            .into_iter()
+            .map(|(_, table)| table.name.to_string())
+            .collect())
+    }
+
+    /// Declares a module like `pub mod schema;` below the other modules
+    pub fn add_mod(&mut self, declaration: &str) -> Result<()> {
+        let module: ItemMod = parse(declaration)?;
+        let file = self.parse()?;
+        let exists = file
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Mod(existing) if existing.ident == module.ident));
+        if !exists {
+            self.insert_item(&file, declaration, &[is_mod_declaration]);
+        }
+        Ok(())
+    }
+
+    /// Adds a use declaration like `use crate::config::ApiContext;` below the other ones
+    pub fn add_use(&mut self, declaration: &str) -> Result<()> {
+        let declaration_tokens = tokens(&parse::<ItemUse>(declaration)?);
+        let file = self.parse()?;
+        let exists = file.items.iter().any(
+            |item| matches!(item, Item::Use(existing) if tokens(existing) == declaration_tokens),
+        );
+        if !exists {
+            self.insert_item(&file, declaration, &[is_use, is_mod_declaration]);
+        }
+        Ok(())
+    }
+
+    /// Adds a struct to the end of the file, unless a struct of the same name exists
+    pub fn add_struct(&mut self, definition: &str) -> Result<()> {
+        let item: ItemStruct = parse(definition)?;
+        if find_struct(&self.parse()?, &item.ident.to_string()).is_none() {
+            self.append(definition);
+        }
+        Ok(())
+    }
+
+    /// Adds the items of `source`, like a rendered template. Use declarations go below the
+    /// other ones, the remaining items to the end of the file unless the file has them.
+    pub fn add_items(&mut self, source: &str) -> Result<()> {
+        let existing = item_names(&self.parse()?);
+        for (item, text) in items(source)? {
+            match item {
+                Item::Use(_) => self.add_use(text)?,
+                item if item_name(&item).is_some_and(|name| existing.contains(&name)) => {}
+                _ => self.append(text),
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the file has any of the items of `source` besides its use declarations
+    pub fn has_items(&self, source: &str) -> Result<bool> {
+        let existing = item_names(&self.parse()?);
+        Ok(items(source)?
+            .iter()
+            .filter_map(|(item, _)| item_name(item))
+            .any(|name| existing.contains(&name)))
+    }
+
+    /// Removes the items of the file that `source` defines again, together with their
+    /// attributes and doc comments
+    pub fn remove_items(&mut self, source: &str) -> Result<()> {
+        let names = items(source)?
+            .iter()
+            .filter_map(|(item, _)| item_name(item))
+            .collect::<Vec<_>>();
+        let file = self.parse()?;
+        // Removed from the end, so the positions of the earlier items still hold
+        for item in file.items.iter().rev() {
+            if item_name(item).is_some_and(|name| names.contains(&name)) {
+                self.remove_item(item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the items of `source` to the end of an inline module like `pub mod sql_types { ... }`
+    /// unless the module has them. `source` is indented like the contents of the module.
+    /// A missing module is created public above the other items.
+    pub fn add_mod_items(&mut self, module: &str, source: &str) -> Result<()> {
+        let file = self.parse()?;
+        let found = file.items.iter().find_map(|item| match item {
+            Item::Mod(item) if item.ident == module => item
+                .content
+                .as_ref()
+                .map(|(brace, items)| (item, brace, items)),
+            _ => None,
+        });
+        let Some((item, brace, existing)) = found else {
+            let items = items(source)?
+                .into_iter()
+                .map(|(_, text)| format!("    {}", text))
+                .collect::<Vec<_>>();
+            let module = format!("pub mod {} {{\n{}\n}}", module, items.join("\n\n"));
+            self.insert_item(&file, &module, &[]);
+            return Ok(());
+        };
+
+        let existing = existing.iter().filter_map(item_name).collect::<Vec<_>>();
+        let indentation = self.indentation(item.span().start().line) + "    ";
+        let items = items(source)?
+            .into_iter()
+            .filter(|(item, _)| !item_name(item).is_some_and(|name| existing.contains(&name)))
+            .map(|(_, text)| format!("{}{}", indentation, text))
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            return Ok(());
+        }
+        let open = brace.span.open();
+        let close = brace.span.close().start();
+        match open.start().line == close.line {
+            true => self.replace(
+                open.end(),
+                close,
+                &format!("\n{}\n{}", items.join("\n\n"), &indentation[4..]),
+            ),
+            false => {
+                let text = items
+                    .iter()
+                    .map(|item| format!("\n{}\n", item))
+                    .collect::<String>();
+                self.insert(
+                    LineColumn {
+                        line: close.line,
+                        column: 0,
+                    },
+                    &text,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a field like `pub db: DbPool` to a struct
+    pub fn add_struct_field(&mut self, name: &str, field: &str) -> Result<()> {
+        let new_field: Field = parse_with(Field::parse_named, field)?;
+        let file = self.parse()?;
+        let item = find_struct(&file, name).context(self.missing(&format!("struct {}", name)))?;
+        let syn::Fields::Named(fields) = &item.fields else {
+            anyhow::bail!(
+                "Struct {} in {} has no named fields",
+                name,
+                self.path.display()
+            );
+        };
+        if !fields
+            .named
+            .iter()
+            .any(|existing| existing.ident == new_field.ident)
+        {
+            self.insert_into_braces(&fields.named, fields.brace_token.span, field);
+        }
+        Ok(())
+    }
+
+    /// Adds a statement like `let context = Context {};` to the beginning of a function.
+    /// A `let` is left out if the function already binds the same name.
+    pub fn add_statement(&mut self, function: &str, statement: &str) -> Result<()> {
+        let new_statement: Stmt = parse(statement)?;
+        let file = self.parse()?;
+        let item = find_function(&file, function)
+            .context(self.missing(&format!("function {}", function)))?;
+        let exists = item
+            .block
+            .stmts
+            .iter()
+            .any(|existing| match (existing, &new_statement) {
+                (Stmt::Local(existing), Stmt::Local(local)) => {
+                    tokens(&existing.pat) == tokens(&local.pat)
+                }
+                _ => tokens(existing) == tokens(&new_statement),
+            });
+        if exists {
+            return Ok(());
+        }
+
+        let indentation = match item.block.stmts.first() {
+            Some(first) => self.indentation(first.span().start().line),
+            None => self.indentation(item.span().start().line) + "    ",
+        };
+        let statement = statement
+            .lines()
+            .collect::<Vec<_>>()
+            .join(&format!("\n{}", indentation));
+        let open = item.block.brace_token.span.open().end();
+        self.insert(open, &format!("\n{}{}", indentation, statement));
+        Ok(())
+    }
+
+    /// Adds a field like `db: pool` to the struct expression of the struct in a function
+    pub fn add_field_value(&mut self, function: &str, name: &str, field: &str) -> Result<()> {
+        let new_field: FieldValue = parse(field)?;
+        let file = self.parse()?;
+        let item = find_function(&file, function)
+            .context(self.missing(&format!("function {}", function)))?;
+        let expressions = Expressions::of(&item.block);
+        let expression = expressions
+            .structs
+            .iter()
+            .find(|expression| {
+                expression
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == name)
+            })
+            .context(self.missing(&format!("{} in function {}", name, function)))?;
+        if !expression
+            .fields
+            .iter()
+            .any(|existing| tokens(&existing.member) == tokens(&new_field.member))
+        {
+            self.insert_into_braces(&expression.fields, expression.brace_token.span, field);
+        }
+        Ok(())
+    }
+
+    /// Adds an element like `Box::new(m20240101_000000_create_post_table::Migration)` to the
+    /// `vec![]` in a function or method, unless the vector has it
+    pub fn add_vec_element(&mut self, function: &str, element: &str) -> Result<()> {
+        let element_tokens = tokens(&parse::<Expr>(element)?);
+        let file = self.parse()?;
+        let block = find_function(&file, function)
+            .map(|item| &*item.block)
+            .or_else(|| find_method(&file, function).map(|item| &item.block))
+            .context(self.missing(&format!("function {}", function)))?;
+        let expressions = Expressions::of(block);
+        let vector = expressions
+            .macros
+            .iter()
+            .find(|mac| mac.path.is_ident("vec"))
+            .context(self.missing(&format!("vec! in function {}", function)))?;
+        let elements = vector
+            .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+            .context(format!("Failed to parse vec! in function {}", function))?;
+        if !elements
+            .iter()
+            .any(|existing| tokens(existing) == element_tokens)
+        {
+            self.insert_into_braces(&elements, *vector.delimiter.span(), element);
+        }
+        Ok(())
+    }
+
+    /// Chains a method call like `.with_state(context)` onto the end of the method chain in a
+    /// function that starts with `root`, for example `Router::new()`
+    pub fn chain_method(&mut self, function: &str, root: &str, call: &str) -> Result<()> {
+        let root_tokens = tokens(&parse::<Expr>(root)?);
+        let new_call: ExprMethodCall = parse(&format!("chain{}", call))?;
+        let file = self.parse()?;
+        let item = find_function(&file, function)
+            .context(self.missing(&format!("function {}", function)))?;
+
+        // Calls are visited from the outside in, so the first one is the whole chain
+        let expressions = Expressions::of(&item.block);
+        let chain = expressions
+            .method_calls
+            .iter()
+            .find(|call| tokens(chain_root(call)) == root_tokens)
+            .context(self.missing(&format!("{} in function {}", root, function)))?;
+
+        let mut calls = vec![];
+        let mut expression = Some(*chain);
+        while let Some(call) = expression {
+            calls.push(call);
+            expression = match &*call.receiver {
+                Expr::MethodCall(receiver) => Some(receiver),
+                _ => None,
+            };
+        }
+        if calls.iter().any(|existing| {
+            existing.method == new_call.method && tokens(&existing.args) == tokens(&new_call.args)
+        }) {
+            return Ok(());
+        }
+
+        let start = chain.span().start();
+        let end = chain.span().end();
+        if start.line != end.line {
+            // Every call of the chain is on its own line
+            let indentation = self.indentation(chain.dot_token.span.start().line);
+            self.insert(end, &format!("\n{}{}", indentation, call));
+        } else if end.column - start.column + call.len() > CHAIN_WIDTH {
+            let indentation = format!("{}    ", self.indentation(start.line));
+            self.insert(end, &format!("\n{}{}", indentation, call));
+            // A root shorter than the indentation keeps its first call, like `cfg.route(...)`
+            let root = chain_root(chain).span();
+            if root.end().column - root.start().column <= 4 {
+                calls.pop();
+            }
+            // The calls are ordered from the end of the chain, so the earlier positions still hold
+            for call in calls {
+                self.insert(call.dot_token.span.start(), &format!("\n{}", indentation));
+            }
+        } else {
+            self.insert(end, call);
+        }
+        Ok(())
+    }
+
+    /// Applies the changes to the fields of a struct, new fields get the visibility of the
+    /// others. A missing struct is left alone, like the update struct of a read only model.
+    pub fn edit_struct_fields(&mut self, name: &str, changes: &[FieldChange]) -> Result<()> {
+        for change in changes {
+            let file = self.parse()?;
+            let Some(syn::Fields::Named(fields)) =
+                find_struct(&file, name).map(|item| &item.fields)
+            else {
+                return Ok(());
+            };
+            let visibility = match fields
+                .named
+                .iter()
+                .any(|field| matches!(field.vis, Visibility::Public(_)))
+            {
+                true => "pub ",
+                false => "",
+            };
+            self.edit_entries(
+                &fields.named,
+                fields.brace_token.span,
+                |field| {
+                    let ident = field.ident.as_ref().expect("Fields are named");
+                    let start = match &field.vis {
+                        Visibility::Inherited => ident.span().start(),
+                        vis => vis.span().start(),
+                    };
+                    (ident.clone(), start)
+                },
+                visibility,
+                change,
+            );
+        }
+        Ok(())
+    }
+
+    /// Applies the changes to the columns of a `diesel::table!`, if the file declares the table
+    pub fn edit_table_columns(&mut self, table: &str, changes: &[FieldChange]) -> Result<()> {
+        for change in changes {
+            let file = self.parse()?;
+            let Some(found) = find_tables(&file)
+                .into_iter()
+                .find(|(_, found)| found.name == table)
+            else {
+                return Ok(());
+            };
+            let (_, table) = found;
+            self.edit_entries(
+                &table.columns,
+                table.brace.span,
+                |column| (column.name.clone(), column.name.span().start()),
+                "",
+                change,
+            );
+        }
+        Ok(())
+    }
+
+    /// Keeps the diesel `belongs_to` associations of a struct in line with its foreign key columns
+    pub fn edit_associations(&mut self, name: &str, changes: &[FieldChange]) -> Result<()> {
+        for change in changes {
+            let file = self.parse()?;
+            let Some(item) = find_struct(&file, name) else {
+                return Ok(());
+            };
+            let association = |column: &str| {
+                item.attrs.iter().find_map(|attribute| {
+                    foreign_key(attribute)
+                        .filter(|foreign_key| foreign_key == column)
+                        .map(|foreign_key| (attribute, foreign_key))
+                })
+            };
+            match change {
+                FieldChange::Remove(column) => {
+                    if let Some((attribute, _)) = association(column) {
+                        let span = attribute.span();
+                        self.remove_lines(span.start(), span.end());
+                    }
+                }
+                FieldChange::Rename(old, new) => {
+                    if let Some((_, foreign_key)) = association(old) {
+                        let span = foreign_key.span();
+                        self.replace(span.start(), span.end(), new);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The derive fails without any association
+        let file = self.parse()?;
+        let Some(item) = find_struct(&file, name) else {
+            return Ok(());
+        };
+        if item
+            .attrs
+            .iter()
+            .any(|attribute| foreign_key(attribute).is_some())
+        {
+            return Ok(());
+        }
+        for attribute in item.attrs.iter().rev() {
+            if !attribute.path().is_ident("derive") {
+                continue;
+            }
+            let derives = attribute
+                .parse_args_with(Punctuated::<SynPath, Token![,]>::parse_terminated)
+                .context(format!("Failed to parse derive of {}", name))?;
+            if let Some(index) = derives
+                .iter()
+                .position(|derive| derive.is_ident("Associations"))
+            {
+                self.remove_entry(&derives, index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps the `diesel::joinable!` entries of a table in line with its foreign key columns
+    pub fn edit_joinables(&mut self, table: &str, changes: &[FieldChange]) -> Result<()> {
+        for change in changes {
+            let file = self.parse()?;
+            let column = match change {
+                FieldChange::Remove(column) | FieldChange::Rename(column, _) => column,
+                _ => continue,
+            };
+            // From the end, so the positions of the earlier items still hold
+            for (item, joinable) in find_joinables(&file).into_iter().rev() {
+                if joinable.child != table || joinable.column != column {
+                    continue;
+                }
+                match change {
+                    FieldChange::Rename(_, new) => {
+                        let span = joinable.column.span();
+                        self.replace(span.start(), span.end(), new);
+                    }
+                    _ => self.remove_item(&Item::Macro(item.clone())),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Changes the return type of a function, if it returns `from`
+    pub fn replace_return_type(&mut self, function: &str, from: &str, to: &str) -> Result<()> {
+        let from_tokens = tokens(&parse::<Type>(from)?);
+        let file = self.parse()?;
+        let item = find_function(&file, function)
+            .context(self.missing(&format!("function {}", function)))?;
+        if let ReturnType::Type(_, return_type) = &item.sig.output {
+            if tokens(return_type) == from_tokens {
+                let span = return_type.span();
+                self.replace(span.start(), span.end(), to);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(&self) -> Result<File> {
+        syn::parse_file(&self.source).context(format!("Failed to parse {}", self.path.display()))
+    }
+
+    fn missing(&self, what: &str) -> String {
+        format!("Failed to find {} in {}", what, self.path.display())
+    }
+
+    /// Inserts the item below the last item of the first group that has one, the items of
+    /// other groups are separated by an empty line. Without any, the item goes to the top.
+    fn insert_item(&mut self, file: &File, item: &str, groups: &[fn(&Item) -> bool]) {
+        for (i, group) in groups.iter().enumerate() {
+            if let Some(last) = file.items.iter().rfind(|item| group(item)) {
+                let separator = if i == 0 { "\n" } else { "\n\n" };
+                self.insert(last.span().end(), &format!("{}{}", separator, item));
+                return;
+            }
+        }
+        match file.items.first() {
+            Some(first) => self.insert(first.span().start(), &format!("{}\n\n", item)),
+            None => self.source.push_str(&format!("{}\n", item)),
+        }
+    }
+
+    /// Inserts an entry into a braced list like the fields of a struct, on its own line unless
+    /// the list is written on one line
+    fn insert_into_braces<T: Spanned, P: Spanned>(
+        &mut self,
+        list: &Punctuated<T, P>,
+        braces: DelimSpan,
+        entry: &str,
+    ) {
+        let open = braces.open().start();
+        let Some(last) = list.pairs().last() else {
+            let indentation = self.indentation(open.line);
+            let start = self.offset(braces.open().end());
+            let end = self.offset(braces.close().start());
+            self.source.replace_range(
+                start..end,
+                &format!("\n{}    {},\n{}", indentation, entry, indentation),
+            );
+            return;
+        };
+
+        let value = last.value().span();
+        if value.start().line == open.line {
+            self.insert(value.end(), &format!(", {}", entry));
+            return;
+        }
+        let indentation = self.indentation(value.start().line);
+        match last.punct() {
+            Some(punct) => self.insert(punct.span().end(), &format!("\n{}{},", indentation, entry)),
+            None => self.insert(value.end(), &format!(",\n{}{}", indentation, entry)),
+        }
+    }
+
+    /// Applies a change to a braced list of named entries like the fields of a struct.
+    /// `entry` gives the name of an entry and where its definition starts after its attributes.
+    fn edit_entries<T: Spanned, P: Spanned>(
+        &mut self,
+        list: &Punctuated<T, P>,
+        braces: DelimSpan,
+        entry: impl Fn(&T) -> (Ident, LineColumn),
+        prefix: &str,
+        change: &FieldChange,
+    ) {
+        let position = |name: &str| list.iter().position(|value| entry(value).0 == name);
+        match change {
+            FieldChange::Add(definition) => match position("created_at") {
+                Some(index) => {
+                    let start = list[index].span().start();
+                    let indentation = self.indentation(start.line);
+                    self.insert(
+                        start,
+                        &format!("{}{},\n{}", prefix, definition, indentation),
+                    );
+                }
+                None => self.insert_into_braces(list, braces, &format!("{}{}", prefix, definition)),
+            },
+            FieldChange::Remove(name) => {
+                if let Some(index) = position(name) {
+                    self.remove_entry(list, index);
+                }
+            }
+            FieldChange::Rename(old, new) => {
+                if let Some(index) = position(old) {
+                    let span = entry(&list[index]).0.span();
+                    self.replace(span.start(), span.end(), new);
+                }
+            }
+            FieldChange::Replace(name, definition) => {
+                if let Some(index) = position(name) {
+                    let (_, start) = entry(&list[index]);
+                    let end = list[index].span().end();
+                    self.replace(start, end, &format!("{}{}", prefix, definition));
+                }
+            }
+        }
+    }
+
+    /// Removes an entry of a list together with the separator in front of it, or the one after
+    /// it for the first entry
+    fn remove_entry<T: Spanned, P: Spanned>(&mut self, list: &Punctuated<T, P>, index: usize) {
+        let pairs = list.pairs().collect::<Vec<_>>();
+        let (start, end) = match (index.checked_sub(1), pairs.get(index + 1)) {
+            (Some(previous), _) => (
+                pairs[previous].value().span().end(),
+                pairs[index].value().span().end(),
+            ),
+            (None, Some(next)) => (
+                pairs[index].value().span().start(),
+                next.value().span().start(),
+            ),
+            (None, None) => (
+                pairs[index].value().span().start(),
+                match pairs[index].punct() {
+                    Some(punct) => punct.span().end(),
+                    None => pairs[index].value().span().end(),
+                },
+            ),
+        };
+        self.replace(start, end, "");
+    }
+
+    /// Removes an item with its attributes and doc comments
+    fn remove_item(&mut self, item: &Item) {
+        let span = item.span();
+        let start = self.offset(LineColumn {
+            line: span.start().line,
+            column: 0,
+        });
+        let mut end = self.offset(span.end());
+        if self.source[end..].starts_with('\n') {
+            end += 1;
+        }
+        // Keep a single empty line between the remaining items
+        if (start == 0 || self.source[..start].ends_with("\n\n"))
+            && self.source[end..].starts_with('\n')
+        {
+            end += 1;
+        }
+        self.source.replace_range(start..end, "");
+    }
+
+    /// Removes the lines from the one of `start` to the one of `end`
+    fn remove_lines(&mut self, start: LineColumn, end: LineColumn) {
+        let start = self.offset(LineColumn {
+            line: start.line,
+            column: 0,
+        });
+        let mut end = self.offset(end);
+        if self.source[end..].starts_with('\n') {
+            end += 1;
+        }
+        self.source.replace_range(start..end, "");
+    }
+
+    fn replace(&mut self, start: LineColumn, end: LineColumn, text: &str) {
+        let start = self.offset(start);
+        let end = self.offset(end);
+        self.source.replace_range(start..end, text);
+    }
+
+    /// Appends an item to the end of the file, after an empty line
+    fn append(&mut self, item: &str) {
+        self.source = match self.source.trim_end() {
+            "" => format!("{}\n", item),
+            source => format!("{}\n\n{}\n", source, item),
+        };
+    }
+
+    fn insert(&mut self, location: LineColumn, text: &str) {
+        let offset = self.offset(location);
+        self.source.insert_str(offset, text);
+    }
+
+    /// Byte offset of a line and column, the column counts characters
+    fn offset(&self, location: LineColumn) -> usize {
+        let line = self
+            .source
+            .split_inclusive('\n')
+            .take(location.line - 1)
+            .map(str::len)
+            .sum::<usize>();
+        let column = self.source[line..]
+            .chars()
+            .take(location.column)
+            .map(char::len_utf8)
+            .sum::<usize>();
+        line + column
+    }
+
+    fn indentation(&self, line: usize) -> String {
+        self.source
+            .lines()
+            .nth(line - 1)
+            .unwrap_or_default()
+            .chars()
+            .take_while(|char| char.is_whitespace())
+            .collect()
+    }
+}
+
+/// Column of a `diesel::table!` like `title -> Text`
+struct Column {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    arrow: Token![->],
+    sql_type: Type,
+}
+
+impl Parse for Column {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            attrs: input.call(Attribute::parse_outer)?,
+            name: input.parse()?,
+            arrow: input.parse()?,
+            sql_type: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Column {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attribute in &self.attrs {
+            attribute.to_tokens(tokens);
+        }
+        self.name.to_tokens(tokens);
+        self.arrow.to_tokens(tokens);
+        self.sql_type.to_tokens(tokens);
+    }
+}
+
+/// Contents of a `diesel::table!`, like `post (id) { id -> Int4, }` after its use declarations
+struct Table {
+    name: Ident,
+    brace: token::Brace,
+    columns: Punctuated<Column, Token![,]>,
+}
+
+impl Parse for Table {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        while input.peek(Token![use]) {
+            input.parse::<ItemUse>()?;
+        }
+        input.call(Attribute::parse_outer)?;
+        let name = input.parse()?;
+        if input.peek(token::Paren) {
+            let primary_key;
+            parenthesized!(primary_key in input);
+            primary_key.parse::<TokenStream>()?;
+        }
+        let columns;
+        let brace = braced!(columns in input);
+        Ok(Self {
+            name,
+            brace,
+            columns: columns.parse_terminated(Column::parse, Token![,])?,
+        })
+    }
+}
+
+/// Contents of a `diesel::joinable!` like `post -> user (author)`
+struct Joinable {
+    child: Ident,
+    parent: Ident,
+    column: Ident,
+}
+
+impl Parse for Joinable {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let child = input.parse()?;
+        input.parse::<Token![->]>()?;
+        let parent = input.parse()?;
+        let column;
+        parenthesized!(column in input);
+        Ok(Self {
+            child,
+            parent,
+            column: column.parse()?,
+        })
+    }
+}
+
+/// Struct expressions, method calls and macros of a function body, from the outside in
+#[derive(Default)]
+struct Expressions<'a> {
+    structs: Vec<&'a ExprStruct>,
+    method_calls: Vec<&'a ExprMethodCall>,
+    macros: Vec<&'a Macro>,
+}
+
+impl<'a> Expressions<'a> {
+    fn of(block: &'a Block) -> Self {
+        let mut expressions = Self::default();
+        expressions.visit_block(block);
+        expressions
+    }
+}
+
+impl<'a> Visit<'a> for Expressions<'a> {
+    fn visit_expr_struct(&mut self, node: &'a ExprStruct) {
+        self.structs.push(node);
+        visit::visit_expr_struct(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'a ExprMethodCall) {
+        self.method_calls.push(node);
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'a Macro) {
+        self.macros.push(node);
+        visit::visit_macro(self, node);
+    }
+}
+
+fn parse<T: syn::parse::Parse>(source: &str) -> Result<T> {
+    syn::parse_str(source).context(format!("Failed to parse `{}`", source))
+}
+
+fn parse_with<T>(parser: fn(syn::parse::ParseStream) -> syn::Result<T>, source: &str) -> Result<T> {
+    syn::parse::Parser::parse_str(parser, source).context(format!("Failed to parse `{}`", source))
+}
+
+/// Tokens of a syntax node, to compare nodes regardless of their formatting
+fn tokens(node: &impl ToTokens) -> String {
+    node.to_token_stream().to_string()
+}
+
+/// Top level items of the source and their text
+fn items(source: &str) -> Result<Vec<(Item, &str)>> {
+    let file = RustFile {
+        path: PathBuf::new(),
+        source: source.to_string(),
+    };
+    Ok(file
+        .parse()?
+        .items
+        .into_iter()
+        .map(|item| {
+            let span = item.span();
+            let start = file.offset(span.start());
+            let end = file.offset(span.end());
+            (item, &source[start..end])
+        })
+        .collect())
+}
+
+/// Kind and name of an item like `struct Post`, implementations are named by their type and
+/// trait and the macros of a diesel schema like `table post`. Use declarations and other macros
+/// have none.
+fn item_name(item: &Item) -> Option<String> {
+    match item {
+        Item::Struct(item) => Some(format!("struct {}", item.ident)),
+        Item::Enum(item) => Some(format!("enum {}", item.ident)),
+        Item::Mod(item) => Some(format!("mod {}", item.ident)),
+        Item::Fn(item) => Some(format!("fn {}", item.sig.ident)),
+        Item::Type(item) => Some(format!("type {}", item.ident)),
+        Item::Const(item) => Some(format!("const {}", item.ident)),
+        Item::Trait(item) => Some(format!("trait {}", item.ident)),
+        Item::Impl(item) => Some(match &item.trait_ {
+            Some((_, path, _)) => format!("impl {} for {}", tokens(path), tokens(&item.self_ty)),
+            None => format!("impl {}", tokens(&item.self_ty)),
+        }),
+        Item::Macro(item) => match item.mac.path.segments.last()?.ident.to_string().as_str() {
+            "table" => Some(format!(
+                "table {}",
+                item.mac.parse_body::<Table>().ok()?.name
+            )),
+            "joinable" => {
+                let joinable = item.mac.parse_body::<Joinable>().ok()?;
+                Some(format!(
+                    "joinable {} -> {}",
+                    joinable.child, joinable.parent
+                ))
+            }
+            name @ "allow_tables_to_appear_in_same_query" => Some(name.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn item_names(file: &File) -> Vec<String> {
+    file.items.iter().filter_map(item_name).collect()
+}
+
+fn find_function<'a>(file: &'a File, name: &str) -> Option<&'a ItemFn> {
+    file.items.iter().find_map(|item| match item {
+        Item::Fn(function) if function.sig.ident == name => Some(function),
+        _ => None,
+    })
+}
+
+/// Method of any implementation in the file
+fn find_method<'a>(file: &'a File, name: &str) -> Option<&'a ImplItemFn> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item) => Some(item),
+            _ => None,
+        })
+        .flat_map(|item| &item.items)
+        .find_map(|item| match item {
+            ImplItem::Fn(method) if method.sig.ident == name => Some(method),
+            _ => None,
+        })
+}
+
+fn find_struct<'a>(file: &'a File, name: &str) -> Option<&'a ItemStruct> {
+    file.items.iter().find_map(|item| match item {
+        Item::Struct(item) if item.ident == name => Some(item),
+        _ => None,
+    })
+}
+
+/// Macros like `diesel::table!` by the last segment of their path
+fn find_macros<'a>(file: &'a File, name: &str) -> Vec<&'a ItemMacro> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Macro(item)
+                if item
+                    .mac
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == name) =>
+            {
+                Some(item)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn find_tables(file: &File) -> Vec<(&ItemMacro, Table)> {
+    find_macros(file, "table")
+        .into_iter()
+        .filter_map(|item| Some((item, item.mac.parse_body().ok()?)))
+        .collect()
+}
+
+fn find_joinables(file: &File) -> Vec<(&ItemMacro, Joinable)> {
+    find_macros(file, "joinable")
+        .into_iter()
+        .filter_map(|item| Some((item, item.mac.parse_body().ok()?)))
+        .collect()
+}
+
+/// Column of a diesel attribute like `#[diesel(belongs_to(User, foreign_key = author))]`
+fn foreign_key(attribute: &Attribute) -> Option<Ident> {
+    let Meta::List(list) = &attribute.meta else {
+        return None;
+    };
+    if !list.path.is_ident("diesel") {
+        return None;
+    }
+    flatten(list.tokens.clone())
+        .windows(3)
+        .find_map(|window| match window {
+            [TokenTree::Ident(key), TokenTree::Punct(punct), TokenTree::Ident(value)]
+                if key == "foreign_key" && punct.as_char() == '=' =>
+            {
+                Some(value.clone())
+            }
+            _ => None,
+        })
+}
+
+/// Tokens of a stream with the ones of its groups in place of the groups
+fn flatten(stream: TokenStream) -> Vec<TokenTree> {
+    stream
+        .into_iter()
+        .flat_map(|tree| match tree {
+            TokenTree::Group(group) => flatten(group.stream()),
+            tree => vec![tree],
+        })
+        .collect()
+}
+
+/// Receiver of the first call of a method chain
+fn chain_root(call: &ExprMethodCall) -> &Expr {
+    match &*call.receiver {
+        Expr::MethodCall(receiver) => chain_root(receiver),
+        receiver => receiver,
+    }
+}
+
+fn is_use(item: &Item) -> bool {
+    matches!(item, Item::Use(_))
+}
+
+/// Modules declared in other files, unlike inline ones like `mod tests { ... }`
+fn is_mod_declaration(item: &Item) -> bool {
+    matches!(item, Item::Mod(module) if module.content.is_none())
+}
+
+#[cfg(test)]
+impl RustFile {
+    pub fn from_source(source: &str) -> Self {
+        Self {
+            path: PathBuf::from("src/lib.rs"),
+            source: source.to_string(),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_mod_and_use() {
+        let mut file = RustFile::from_source(
+            "pub mod app;\n\n// Settings\nuse app::Settings;\n\nfn main() {}\n",
+        );
+        file.add_mod("mod database;").unwrap();
+        file.add_use("use database::{DatabaseSettings, DbPool};")
+            .unwrap();
+        file.add_use("use database::{DatabaseSettings,DbPool};")
+            .unwrap();
+        file.add_mod("pub mod database;").unwrap();
+        assert_eq!(
+            file.source,
+            "pub mod app;\nmod database;\n\n// Settings\nuse app::Settings;\nuse database::{DatabaseSettings, DbPool};\n\nfn main() {}\n"
+        );
+
+        let mut file = RustFile::from_source("use axum::Router;\n");
+        file.add_mod("mod post;").unwrap();
+        assert_eq!(file.source, "mod post;\n\nuse axum::Router;\n");
+
+        let mut file = RustFile::from_source("mod routes;\n\nfn main() {}\n");
+        file.add_use("use crate::config::ApiContext;").unwrap();
+        assert_eq!(
+            file.source,
+            "mod routes;\n\nuse crate::config::ApiContext;\n\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_struct_field() {
+        let mut file = RustFile::from_source(
+            "#[derive(Clone)]\npub struct Settings {\n  // The application\n  pub application: App\n}\n\npub struct Context {}\n",
+        );
+        file.add_struct_field("Settings", "pub database: DatabaseSettings")
+            .unwrap();
+        file.add_struct_field("Settings", "pub database: DatabaseSettings")
+            .unwrap();
+        file.add_struct_field("Context", "pub db: DbPool").unwrap();
+        file.add_struct("pub struct Context {\n    pub db: DbPool,\n}")
+            .unwrap();
+        assert_eq!(
+            file.source,
+            "#[derive(Clone)]\npub struct Settings {\n  // The application\n  pub application: App,\n  pub database: DatabaseSettings\n}\n\npub struct Context {\n    pub db: DbPool,\n}\n"
+        );
+        assert!(file.add_struct_field("Missing", "pub db: DbPool").is_err());
+
+        file.add_struct("#[derive(Clone)]\npub struct ApiContext {\n    pub db: DbPool,\n}")
+            .unwrap();
+        assert!(file
+            .source
+            .ends_with("}\n\n#[derive(Clone)]\npub struct ApiContext {\n    pub db: DbPool,\n}\n"));
+    }
+
+    #[test]
+    fn test_add_statement() {
+        let mut file = RustFile::from_source(
+            "pub async fn build(settings: Settings) -> Result<()> {\n    tracing::info!(\"Creating router...\");\n    run().await\n}\n",
+        );
+        file.add_statement("build", "let api_context = ApiContext {};")
+            .unwrap();
+        file.add_field_value(
+            "build",
+            "ApiContext",
+            "db: settings.database.get_connection_pool()",
+        )
+        .unwrap();
+        file.add_statement("build", "let api_context = Context {\n    db: pool,\n};")
+            .unwrap();
+        file.add_field_value("build", "ApiContext", "db: pool")
+            .unwrap();
+        assert_eq!(
+            file.source,
+            "pub async fn build(settings: Settings) -> Result<()> {\n    let api_context = ApiContext {\n        db: settings.database.get_connection_pool(),\n    };\n    tracing::info!(\"Creating router...\");\n    run().await\n}\n"
+        );
+
+        file.add_field_value("build", "ApiContext", "cache: cache")
+            .unwrap();
+        assert!(file.source.contains(
+            "        db: settings.database.get_connection_pool(),\n        cache: cache,\n    };"
+        ));
+    }
+
+    #[test]
+    fn test_chain_method() {
+        let mut file = RustFile::from_source(
+            r#"pub async fn build(settings: Settings) -> Result<()> {
+    let api_router = Router::new()
+        .nest("/api", api_routes()) // All routes
+        .layer(cors);
+
+    HttpServer::new(move || {
+        App::new()
+            .service(web::scope("/api").configure(api_routes))
+    })
+}
+
+pub fn routes() -> Router {
+    Router::new().route("/ping", get(ping))
+}
+"#,
+        );
+        file.chain_method("build", "Router::new()", ".with_state(api_context.clone())")
+            .unwrap();
+        file.chain_method("build", "Router::new()", ".with_state(api_context.clone())")
+            .unwrap();
+        file.chain_method(
+            "build",
+            "App::new()",
+            ".app_data(web::Data::new(api_context.clone()))",
+        )
+        .unwrap();
+        file.chain_method(
+            "routes",
+            "Router::new()",
+            ".nest(\"/posts\", post::routes())",
+        )
+        .unwrap();
+        assert!(file.source.contains(
+            "    Router::new()\n        .route(\"/ping\", get(ping))\n        .nest(\"/posts\", post::routes())\n}"
+        ));
+        file.chain_method(
+            "routes",
+            "Router::new()",
+            ".nest(\"/blog-categories\", blog_category::routes())",
+        )
+        .unwrap();
+        file.replace_return_type("routes", "Router", "Router<ApiContext>")
+            .unwrap();
+        file.replace_return_type("build", "Router", "Router<ApiContext>")
+            .unwrap();
+        assert_eq!(
+            file.source,
+            r#"pub async fn build(settings: Settings) -> Result<()> {
+    let api_router = Router::new()
+        .nest("/api", api_routes()) // All routes
+        .layer(cors)
+        .with_state(api_context.clone());
+
+    HttpServer::new(move || {
+        App::new()
+            .service(web::scope("/api").configure(api_routes))
+            .app_data(web::Data::new(api_context.clone()))
+    })
+}
+
+pub fn routes() -> Router<ApiContext> {
+    Router::new()
+        .route("/ping", get(ping))
+        .nest("/posts", post::routes())
+        .nest("/blog-categories", blog_category::routes())
+}
+"#
+        );
+        assert!(file
+            .chain_method("build", "Missing::new()", ".x()")
+            .is_err());
+
+        let mut file = RustFile::from_source(
+            "pub fn routes(cfg: &mut web::ServiceConfig) {\n    cfg.route(\"/ping\", web::get().to(ping));\n}\n",
+        );
+        file.chain_method(
+            "routes",
+            "cfg",
+            ".service(web::scope(\"/posts\").configure(post::routes))",
+        )
+        .unwrap();
+        assert_eq!(
+            file.source,
+            "pub fn routes(cfg: &mut web::ServiceConfig) {\n    cfg.route(\"/ping\", web::get().to(ping))\n        .service(web::scope(\"/posts\").configure(post::routes));\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_items() {
+        let model = r#"use chrono::{offset::Utc, DateTime};
+use serde::Serialize;
+
+/// A post
+#[derive(Serialize)]
+pub struct Post {
+    id: i32,
+}
+
+impl From<NewPost> for post::ActiveModel {
+    fn from(value: NewPost) -> Self {
+        todo!()
+    }
+}
+"#;
+        let mut file = RustFile::new(Path::new("src/common/models.rs"));
+        file.add_items(model).unwrap();
+        assert_eq!(file.source, model);
+
+        let comment = "use serde::Serialize;\nuse sqlx::FromRow;\n\n#[derive(FromRow)]\npub struct Comment {\n    id: i32,\n}\n";
+        assert!(!file.has_items(comment).unwrap());
+        file.add_items(comment).unwrap();
+        file.add_items(comment).unwrap();
+        assert!(file.has_items(comment).unwrap());
+        assert_eq!(file.source.matches("use serde::Serialize;").count(), 1);
+        assert!(file.source.starts_with(
+            "use chrono::{offset::Utc, DateTime};\nuse serde::Serialize;\nuse sqlx::FromRow;\n\n/// A post"
+        ));
+        assert!(file
+            .source
+            .ends_with("}\n\n#[derive(FromRow)]\npub struct Comment {\n    id: i32,\n}\n"));
+    }
+
+    #[test]
+    fn test_remove_items() {
+        let mut file = RustFile::from_source(
+            "use serde::Serialize;\n\n/// A post\n#[derive(Serialize)]\npub struct Post {\n    id: i32,\n}\n\npub struct NewPost {}\n\nimpl NewPost {\n    fn apply(self) {}\n}\n\npub struct Comment {}\n",
+        );
+        file.remove_items("use serde::Serialize;\n\npub struct Post {}\n\nimpl NewPost {}\n")
+            .unwrap();
+        assert_eq!(
+            file.source,
+            "use serde::Serialize;\n\npub struct NewPost {}\n\npub struct Comment {}\n"
+        );
+    }
+
+    #[test]
+    fn test_edit_struct_fields() {
+        let mut file = RustFile::from_source(
+            r#"#[derive(Queryable, Associations, Serialize)]
+#[diesel(belongs_to(User, foreign_key = author))]
+#[diesel(belongs_to(Tag, foreign_key = tag))]
+pub struct Post {
+    id: i32,
+    author: i32,
+    tag: i32,
+    /// The text
+    body: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+pub struct NewPost {
+    pub author: i32,
+    pub tag: i32,
+    pub body: Option<String>,
+}
+"#,
+        );
+        let changes = vec![
+            FieldChange::Add("views: i32".to_string()),
+            FieldChange::Remove("author".to_string()),
+            FieldChange::Rename("body".to_string(), "content".to_string()),
+            FieldChange::Rename("tag".to_string(), "label".to_string()),
+            FieldChange::Replace("id".to_string(), "id: uuid::Uuid".to_string()),
+        ];
+        file.edit_struct_fields("Post", &changes).unwrap();
+        file.edit_struct_fields("NewPost", &changes).unwrap();
+        file.edit_struct_fields("UpdatePost", &changes).unwrap();
+        file.edit_associations("Post", &changes).unwrap();
+        assert_eq!(
+            file.source,
+            r#"#[derive(Queryable, Associations, Serialize)]
+#[diesel(belongs_to(Tag, foreign_key = label))]
+pub struct Post {
+    id: uuid::Uuid,
+    label: i32,
+    /// The text
+    content: Option<String>,
+    views: i32,
+    created_at: DateTime<Utc>,
+}
+
+pub struct NewPost {
+    pub label: i32,
+    pub content: Option<String>,
+    pub views: i32,
+}
+"#
+        );
+
+        file.edit_associations("Post", &[FieldChange::Remove("label".to_string())])
+            .unwrap();
+        assert!(file
+            .source
+            .starts_with("#[derive(Queryable, Serialize)]\npub struct Post {"));
+    }
+
+    #[test]
+    fn test_edit_table_columns() {
+        let mut file = RustFile::from_source(
+            r#"diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PostStatus;
+
+    post (id) {
+        id -> Int4,
+        author -> Int4,
+        tag -> Int4,
+        status -> PostStatus,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::joinable!(post -> users (author));
+diesel::joinable!(post -> tag (tag));
+"#,
+        );
+        let changes = vec![
+            FieldChange::Add("views -> Int4".to_string()),
+            FieldChange::Remove("author".to_string()),
+            FieldChange::Rename("tag".to_string(), "label".to_string()),
+            FieldChange::Replace("status".to_string(), "status -> Text".to_string()),
+        ];
+        file.edit_table_columns("post", &changes).unwrap();
+        file.edit_joinables("post", &changes).unwrap();
+        file.edit_table_columns("comment", &changes).unwrap();
+        assert_eq!(
+            file.source,
+            r#"diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PostStatus;
+
+    post (id) {
+        id -> Int4,
+        label -> Int4,
+        status -> Text,
+        views -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::joinable!(post -> tag (label));
+"#
+        );
+    }
+}
@@ -2,12 +2,11 @@ use anyhow::{Context, Result};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use std::fs::OpenOptions;
-use std::io::Write;
 use std::path::Path;
 use std::{default::Default, fs, str::FromStr};
 
-use crate::generate::FromTerm;
+use crate::files;
+use crate::generate::{FromTerm, Resource};
 
 #[derive(Deserialize, Serialize, Debug, Default)]
 #[serde(deny_unknown_fields)]
@@ -15,26 +14,42 @@ pub struct Config {
     #[serde(default)]
     pub api_framework: ApiFramework,
     pub database: Option<Database>,
+    /// Resources created with `schmiede generate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resources: Vec<Resource>,
 }
 
 impl Config {
     pub fn from_file() -> Result<Self> {
-        let config = fs::read_to_string("schmiede.toml")?;
+        let config = files::read_to_string("schmiede.toml")?;
         let config: Config = toml::from_str(&config)?;
         Ok(config)
     }
 
     pub fn update_config(&self) -> Result<()> {
-        let mut file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open("schmiede.toml")?;
-
         let config = toml::to_string_pretty(self)?;
-        file.write_all(&config.into_bytes())?;
+        files::write("schmiede.toml", config).context("Failed to write config")?;
         Ok(())
     }
 
+    pub fn resource(&self, table: &str) -> Option<&Resource> {
+        self.resources
+            .iter()
+            .find(|resource| resource.is_table(table))
+    }
+
+    /// Replaces the resource with the same name or adds it
+    pub fn register_resource(&mut self, resource: Resource) {
+        match self
+            .resources
+            .iter_mut()
+            .find(|registered| registered.is_table(&resource.name))
+        {
+            Some(registered) => *registered = resource,
+            None => self.resources.push(resource),
+        }
+    }
+
     pub fn create_config_toml(&self, project_path: &Path) -> Result<()> {
         let config = toml::to_string_pretty(self)?;
         fs::write(project_path.join("schmiede.toml"), config).context("Failed to write config")?;
@@ -68,6 +83,7 @@ impl ConfigBuilder {
         Config {
             api_framework: self.api_framework.clone(), // Assuming api_framework is now required
             database: self.database.clone(),
+            resources: vec![],
         }
     }
 }
@@ -77,13 +93,14 @@ impl ConfigBuilder {
 pub enum ApiFramework {
     #[default]
     Axum,
-    //Actix,
+    Actix,
 }
 
 impl Display for ApiFramework {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Axum => write!(f, "axum"),
+            Self::Actix => write!(f, "actix"),
         }
     }
 }
@@ -93,7 +110,7 @@ impl FromTerm<ApiFramework> for ApiFramework {
         term: &console::Term,
         theme: &dialoguer::theme::ColorfulTheme,
     ) -> Result<ApiFramework> {
-        let options = ["Axum"];
+        let options = ["Axum", "Actix"];
         let index = dialoguer::Select::with_theme(theme)
             .with_prompt("Which api framework do you want to use?")
             .items(&options)
@@ -109,7 +126,7 @@ impl FromStr for ApiFramework {
     fn from_str(input: &str) -> Result<Self> {
         match input.to_lowercase().as_str() {
             "axum" => Ok(ApiFramework::Axum),
-            //"actix" => Ok(ApiFramework::Actix),
+            "actix" => Ok(ApiFramework::Actix),
             _ => anyhow::bail!("Failed to get api framework from str"),
         }
     }
@@ -121,6 +138,14 @@ pub struct Database {
     pub database_type: DatabaseType,
     #[clap(short = 'd', long, value_enum)]
     pub database_driver: DatabaseDriver,
+    /// Crate the generated models use for numeric columns
+    #[clap(skip)]
+    #[serde(default)]
+    pub decimal: DecimalCrate,
+    /// Extension that generates uuid ids, only used by PostgreSQL
+    #[clap(short = 'u', long, default_value = "uuid-ossp")]
+    #[serde(default)]
+    pub uuid_extension: UuidExtension,
 }
 
 impl Database {
@@ -128,6 +153,8 @@ impl Database {
         Self {
             database_type,
             database_driver,
+            decimal: DecimalCrate::default(),
+            uuid_extension: UuidExtension::default(),
         }
     }
 }
@@ -136,7 +163,8 @@ impl Database {
 #[serde(rename_all = "lowercase")]
 pub enum DatabaseType {
     PostgreSQL,
-    //MySQL,
+    MySQL,
+    Sqlite,
 }
 
 impl FromTerm<Option<DatabaseType>> for DatabaseType {
@@ -144,7 +172,7 @@ impl FromTerm<Option<DatabaseType>> for DatabaseType {
         term: &console::Term,
         theme: &dialoguer::theme::ColorfulTheme,
     ) -> Result<Option<DatabaseType>> {
-        let options = ["PostgreSQL", "None"];
+        let options = ["PostgreSQL", "MySQL", "SQLite", "None"];
 
         let index = dialoguer::Select::with_theme(theme)
             .with_prompt("Which database do you want to use?")
@@ -165,17 +193,89 @@ impl FromStr for DatabaseType {
     fn from_str(input: &str) -> Result<Self> {
         match input.to_lowercase().as_str() {
             "postgresql" => Ok(DatabaseType::PostgreSQL),
-            //"sqlite" => Ok(Database::Sqlite),
+            "mysql" => Ok(DatabaseType::MySQL),
+            "sqlite" => Ok(DatabaseType::Sqlite),
             _ => anyhow::bail!("Failed to get database from str"),
         }
     }
 }
 
+#[derive(Deserialize, Serialize, Debug, Default, PartialEq, Clone)]
+pub enum DecimalCrate {
+    #[default]
+    #[serde(rename = "rust_decimal")]
+    RustDecimal,
+    #[serde(rename = "bigdecimal")]
+    BigDecimal,
+}
+
+impl DecimalCrate {
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            DecimalCrate::RustDecimal => "rust_decimal::Decimal",
+            DecimalCrate::BigDecimal => "bigdecimal::BigDecimal",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum UuidExtension {
+    /// `uuid_generate_v4()`
+    #[default]
+    UuidOssp,
+    /// `gen_random_uuid()`
+    Pgcrypto,
+    /// `gen_random_uuid()` without an extension, available since PostgreSQL 13
+    Builtin,
+}
+
+impl UuidExtension {
+    pub fn function(&self) -> &'static str {
+        match self {
+            UuidExtension::UuidOssp => "uuid_generate_v4()",
+            UuidExtension::Pgcrypto | UuidExtension::Builtin => "gen_random_uuid()",
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            UuidExtension::UuidOssp => Some("uuid-ossp"),
+            UuidExtension::Pgcrypto => Some("pgcrypto"),
+            UuidExtension::Builtin => None,
+        }
+    }
+
+    pub fn create_statement(&self) -> Option<String> {
+        self.name()
+            .map(|name| format!("CREATE EXTENSION IF NOT EXISTS \"{}\";", name))
+    }
+
+    pub fn drop_statement(&self) -> Option<String> {
+        self.name()
+            .map(|name| format!("DROP EXTENSION IF EXISTS \"{}\";", name))
+    }
+}
+
+impl FromStr for UuidExtension {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        match input.to_lowercase().as_str() {
+            "uuid-ossp" => Ok(UuidExtension::UuidOssp),
+            "pgcrypto" => Ok(UuidExtension::Pgcrypto),
+            "builtin" => Ok(UuidExtension::Builtin),
+            _ => anyhow::bail!("Failed to get uuid extension from str"),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum DatabaseDriver {
     Sqlx,
     Diesel,
+    SeaOrm,
 }
 
 impl FromTerm<DatabaseDriver> for DatabaseDriver {
@@ -183,7 +283,7 @@ impl FromTerm<DatabaseDriver> for DatabaseDriver {
         term: &console::Term,
         theme: &dialoguer::theme::ColorfulTheme,
     ) -> Result<DatabaseDriver> {
-        let options = ["Sqlx", "Diesel"];
+        let options = ["Sqlx", "Diesel", "SeaORM"];
         let index = dialoguer::Select::with_theme(theme)
             .with_prompt("Which database driver do you want to use?")
             .items(&options)
@@ -200,6 +300,7 @@ impl FromStr for DatabaseDriver {
         match input.to_lowercase().as_str() {
             "sqlx" => Ok(DatabaseDriver::Sqlx),
             "diesel" => Ok(DatabaseDriver::Diesel),
+            "seaorm" | "sea-orm" => Ok(DatabaseDriver::SeaOrm),
             _ => anyhow::bail!("Failed to get database driver from str"),
         }
     }
@@ -212,7 +313,7 @@ mod tests {
     #[test]
     fn test_api_framework_from_values() {
         let invalid = "invalid";
-        let options = ["axum"];
+        let options = ["axum", "actix"];
 
         for option in options.iter() {
             assert!(ApiFramework::from_str(option).is_ok());
@@ -221,10 +322,22 @@ mod tests {
         assert!(ApiFramework::from_str(invalid).is_err());
     }
 
+    #[test]
+    fn test_database_type_from_values() {
+        let invalid = "invalid";
+        let options = ["PostgreSQL", "MySQL", "SQLite"];
+
+        for value in options.iter() {
+            assert!(DatabaseType::from_str(value).is_ok());
+        }
+
+        assert!(DatabaseType::from_str(invalid).is_err());
+    }
+
     #[test]
     fn test_database_driver_from_values() {
         let invalid = "invalid";
-        let options = ["Sqlx", "Diesel"];
+        let options = ["Sqlx", "Diesel", "SeaORM"];
 
         for value in options.iter() {
             assert!(DatabaseDriver::from_str(value).is_ok());
@@ -233,6 +346,45 @@ mod tests {
         assert!(DatabaseDriver::from_str(invalid).is_err());
     }
 
+    #[test]
+    fn test_decimal_crate_from_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [database]
+            database_type = "postgresql"
+            database_driver = "sqlx"
+            decimal = "bigdecimal"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.database.unwrap().decimal, DecimalCrate::BigDecimal);
+
+        let config: Config = toml::from_str(
+            r#"
+            [database]
+            database_type = "postgresql"
+            database_driver = "sqlx"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.database.unwrap().decimal, DecimalCrate::RustDecimal);
+    }
+
+    #[test]
+    fn test_uuid_extension_from_values() {
+        let extension = UuidExtension::from_str("pgcrypto").unwrap();
+        assert_eq!(extension.function(), "gen_random_uuid()");
+        assert_eq!(
+            extension.create_statement().as_deref(),
+            Some("CREATE EXTENSION IF NOT EXISTS \"pgcrypto\";")
+        );
+
+        let extension = UuidExtension::from_str("builtin").unwrap();
+        assert!(extension.create_statement().is_none());
+
+        assert!(UuidExtension::from_str("invalid").is_err());
+    }
+
     /*
      * Should be tested in integration tests
     #[test]
@@ -0,0 +1,438 @@
+use console::style;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Result;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::{cmp, fs};
+
+/// Lines of context around the changes of a diff
+const CONTEXT: usize = 3;
+
+/// Change set of the files that `add` and `generate` wrote.
+/// Every write is kept in memory and later reads see the staged contents. The change set is
+/// committed once the command succeeded, with `--dry-run` it is printed as a diff instead, so
+/// a failing command leaves the project untouched.
+static STAGED: Mutex<Option<Staged>> = Mutex::new(None);
+
+/// Number of writes and new directories that changed the project, staged or not
+static CHANGES: AtomicUsize = AtomicUsize::new(0);
+
+/// Files and directories that were written since `stage`
+#[derive(Debug, Default)]
+struct Staged {
+    files: BTreeMap<PathBuf, String>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl Staged {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        match self.files.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => fs::read_to_string(path),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path) || self.dirs.contains(path) || path.exists()
+    }
+
+    fn write(&mut self, path: &Path, contents: String) {
+        self.files.insert(path.to_path_buf(), contents);
+    }
+
+    fn create_dir_all(&mut self, path: &Path) {
+        for dir in path.ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+            self.dirs.insert(dir.to_path_buf());
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let mut entries = match fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| normalize(&entry.path()))
+                .collect::<BTreeSet<_>>(),
+            Err(_) if self.dirs.contains(path) => BTreeSet::new(),
+            Err(err) => return Err(err),
+        };
+        let staged = self.files.keys().chain(self.dirs.iter());
+        entries.extend(staged.filter(|entry| entry.parent() == Some(path)).cloned());
+        Ok(entries.into_iter().collect())
+    }
+
+    /// Writes the changes to the project, restores it if a write fails
+    fn commit(&self) -> Result<()> {
+        let mut created = vec![];
+        let mut originals = vec![];
+        let result = self.write_all(&mut created, &mut originals);
+        if result.is_err() {
+            for (path, original) in originals.into_iter().rev() {
+                let _ = match original {
+                    Some(contents) => fs::write(path, contents),
+                    None => fs::remove_file(path),
+                };
+            }
+            for dir in created.into_iter().rev() {
+                let _ = fs::remove_dir(dir);
+            }
+        }
+        result
+    }
+
+    /// Keeps the created directories and the original contents of the written files
+    fn write_all<'a>(
+        &'a self,
+        created: &mut Vec<&'a Path>,
+        originals: &mut Vec<(&'a Path, Option<Vec<u8>>)>,
+    ) -> Result<()> {
+        // Parents are sorted before their children
+        for dir in self.dirs.iter().filter(|dir| !dir.exists()) {
+            fs::create_dir(dir)?;
+            created.push(dir);
+        }
+        for (path, contents) in &self.files {
+            let original = match path.exists() {
+                true => Some(fs::read(path)?),
+                false => None,
+            };
+            originals.push((path, original));
+            fs::write(path, contents)?;
+        }
+        Ok(())
+    }
+
+    /// Diffs of the staged files against the project, new directories are listed by name
+    fn diffs(&self) -> Vec<(PathBuf, Vec<String>)> {
+        let dirs = self
+            .dirs
+            .iter()
+            .filter(|dir| !dir.exists())
+            .filter(|dir| !self.files.keys().any(|file| file.starts_with(dir)))
+            .map(|dir| (dir.clone(), vec![]));
+        let files = self.files.iter().filter_map(|(path, contents)| {
+            let old = fs::read_to_string(path).unwrap_or_default();
+            let diff = unified_diff(&old, contents);
+            (!diff.is_empty()).then(|| (path.clone(), diff))
+        });
+        dirs.chain(files).collect()
+    }
+}
+
+/// Keeps all following writes in memory until they are committed
+pub fn stage() {
+    *STAGED.lock().unwrap() = Some(Staged::default());
+}
+
+/// Writes the staged changes to the project, on failure it is left as it was
+pub fn commit() -> Result<()> {
+    match STAGED.lock().unwrap().take() {
+        Some(staged) => staged.commit(),
+        None => Ok(()),
+    }
+}
+
+pub fn read_to_string(path: impl AsRef<Path>) -> Result<String> {
+    let path = normalize(path.as_ref());
+    match STAGED.lock().unwrap().as_ref() {
+        Some(staged) => staged.read_to_string(&path),
+        None => fs::read_to_string(path),
+    }
+}
+
+pub fn exists(path: impl AsRef<Path>) -> bool {
+    let path = normalize(path.as_ref());
+    match STAGED.lock().unwrap().as_ref() {
+        Some(staged) => staged.exists(&path),
+        None => path.exists(),
+    }
+}
+
+/// Writes the file, files that already have the contents are left alone
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = normalize(path.as_ref());
+    if read_to_string(&path).is_ok_and(|current| current.as_bytes() == contents.as_ref()) {
+        return Ok(());
+    }
+    CHANGES.fetch_add(1, Ordering::Relaxed);
+    match STAGED.lock().unwrap().as_mut() {
+        Some(staged) => {
+            let contents = String::from_utf8_lossy(contents.as_ref()).to_string();
+            staged.write(&path, contents);
+            Ok(())
+        }
+        None => fs::write(path, contents),
+    }
+}
+
+pub fn create_dir_all(path: impl AsRef<Path>) -> Result<()> {
+    let path = normalize(path.as_ref());
+    if exists(&path) {
+        return Ok(());
+    }
+    CHANGES.fetch_add(1, Ordering::Relaxed);
+    match STAGED.lock().unwrap().as_mut() {
+        Some(staged) => {
+            staged.create_dir_all(&path);
+            Ok(())
+        }
+        None => fs::create_dir_all(path),
+    }
+}
+
+/// Number of changes so far, the same number before and after a command means it had
+/// nothing to do
+pub fn changes() -> usize {
+    CHANGES.load(Ordering::Relaxed)
+}
+
+/// Paths of the entries of a directory, sorted by name
+pub fn read_dir(path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let path = normalize(path.as_ref());
+    match STAGED.lock().unwrap().as_ref() {
+        Some(staged) => staged.read_dir(&path),
+        None => {
+            let mut entries = fs::read_dir(path)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| normalize(&entry.path()))
+                .collect::<Vec<_>>();
+            entries.sort();
+            Ok(entries)
+        }
+    }
+}
+
+/// Prints the staged changes as colored diffs instead of writing them
+pub fn print_changes() {
+    let Some(staged) = STAGED.lock().unwrap().take() else {
+        return;
+    };
+    let diffs = staged.diffs();
+    if diffs.is_empty() {
+        println!("{}", style("No changes").dim());
+    }
+
+    for (path, diff) in diffs {
+        if diff.is_empty() {
+            println!(
+                "{}",
+                style(format!("new directory {}/", path.display())).bold()
+            );
+            continue;
+        }
+        match path.exists() {
+            true => println!("{}", style(format!("--- a/{}", path.display())).bold()),
+            false => println!("{}", style("--- /dev/null").bold()),
+        }
+        println!("{}", style(format!("+++ b/{}", path.display())).bold());
+        for line in diff {
+            match line.chars().next() {
+                Some('+') => println!("{}", style(line).green()),
+                Some('-') => println!("{}", style(line).red()),
+                Some('@') => println!("{}", style(line).cyan()),
+                _ => println!("{}", line),
+            }
+        }
+    }
+}
+
+/// Removes `./` so the same file is always staged under the same path
+fn normalize(path: &Path) -> PathBuf {
+    let path = path
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect::<PathBuf>();
+    match path.as_os_str().is_empty() {
+        true => PathBuf::from("."),
+        false => path,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Lines of both files, matched through their longest common subsequence
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => cmp::max(lcs[i + 1][j], lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = vec![];
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Hunks of a unified diff, empty if the contents are the same
+fn unified_diff(old: &str, new: &str) -> Vec<String> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    let lines = diff_lines(&old, &new);
+
+    // Ranges of the lines that are shown, changes that are close share a hunk
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for (i, _) in lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Same(_)))
+    {
+        let start = i.saturating_sub(CONTEXT);
+        let end = cmp::min(i + CONTEXT + 1, lines.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let in_old = |line: &&Line| !matches!(line, Line::Added(_));
+    let in_new = |line: &&Line| !matches!(line, Line::Removed(_));
+    let mut diff = vec![];
+    for (start, end) in hunks {
+        let old_start = lines[..start].iter().filter(in_old).count();
+        let new_start = lines[..start].iter().filter(in_new).count();
+        let old_length = lines[start..end].iter().filter(in_old).count();
+        let new_length = lines[start..end].iter().filter(in_new).count();
+        diff.push(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + cmp::min(old_length, 1),
+            old_length,
+            new_start + cmp::min(new_length, 1),
+            new_length
+        ));
+        for line in &lines[start..end] {
+            diff.push(match line {
+                Line::Same(line) => format!(" {}", line),
+                Line::Removed(line) => format!("-{}", line),
+                Line::Added(line) => format!("+{}", line),
+            });
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "mod a;\nmod b;\n\nfn main() {\n    a::run();\n}\n";
+        let new = "mod a;\nmod b;\nmod c;\n\nfn main() {\n    c::run();\n}\n";
+        assert_eq!(
+            unified_diff(old, new),
+            vec![
+                "@@ -1,6 +1,7 @@",
+                " mod a;",
+                " mod b;",
+                "+mod c;",
+                " ",
+                " fn main() {",
+                "-    a::run();",
+                "+    c::run();",
+                " }",
+            ]
+        );
+
+        assert_eq!(
+            unified_diff("", "a\nb\n"),
+            vec!["@@ -0,0 +1,2 @@", "+a", "+b"]
+        );
+        assert!(unified_diff(old, old).is_empty());
+    }
+
+    #[test]
+    fn test_staged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+
+        let mut staged = Staged::default();
+        let migrations = dir.path().join("migrations/2024-01-01-000000_post");
+        staged.create_dir_all(&migrations);
+        staged.write(
+            &migrations.join("up.sql"),
+            "CREATE TABLE post ();".to_string(),
+        );
+        staged.write(
+            &dir.path().join("Cargo.toml"),
+            "[package]\nname = \"api\"\n".to_string(),
+        );
+
+        assert!(staged.exists(&migrations.join("up.sql")));
+        assert!(!migrations.exists());
+        assert_eq!(
+            staged
+                .read_to_string(&dir.path().join("Cargo.toml"))
+                .unwrap(),
+            "[package]\nname = \"api\"\n"
+        );
+        assert_eq!(
+            staged.read_dir(dir.path()).unwrap(),
+            vec![dir.path().join("Cargo.toml"), dir.path().join("migrations")]
+        );
+
+        let diffs = staged.diffs();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(
+            diffs[0].1,
+            vec!["@@ -1,1 +1,2 @@", " [package]", "+name = \"api\""]
+        );
+        assert_eq!(
+            diffs[1].1,
+            vec!["@@ -0,0 +1,1 @@", "+CREATE TABLE post ();"]
+        );
+    }
+
+    #[test]
+    fn test_commit_restores_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(dir.path().join("routes"), "").unwrap();
+
+        let mut staged = Staged::default();
+        staged.create_dir_all(&dir.path().join("migrations"));
+        staged.write(
+            &dir.path().join("Cargo.toml"),
+            "[dependencies]\n".to_string(),
+        );
+        staged.write(&dir.path().join("migrations/up.sql"), String::new());
+        // Fails as routes is a file
+        staged.write(&dir.path().join("routes/post.rs"), String::new());
+
+        assert!(staged.commit().is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("Cargo.toml")).unwrap(),
+            "[package]\n"
+        );
+        assert!(!dir.path().join("migrations").exists());
+
+        staged.files.remove(&dir.path().join("routes/post.rs"));
+        staged.commit().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("Cargo.toml")).unwrap(),
+            "[dependencies]\n"
+        );
+        assert!(dir.path().join("migrations/up.sql").exists());
+    }
+}
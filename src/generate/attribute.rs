@@ -1,30 +1,103 @@
-use super::data_types::DataType;
+use super::data_types::{DataType, EnumType, IDType};
+use super::reference::{ForeignKey, Reference};
 use super::{FromClap, FromTerm};
+use crate::config::Config;
 use anyhow::{Context, Result};
 use console::{style, Term};
+use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Confirm, FuzzySelect, Input};
+use std::{fmt::Display, str::FromStr};
+
+/// Characters that start a column modifier after the data type
+const MODIFIERS: [char; 5] = ['!', '#', '=', '<', '>'];
 
 #[derive(Debug, Clone)]
 pub struct Attribute {
     pub name: String,
     pub data_type: DataType,
     pub optional: bool,
+    pub reference: Option<Reference>,
+    pub modifiers: Modifiers,
+}
+
+/// Constraints of a column, written after its data type
+#[derive(Debug, Clone, Default)]
+pub struct Modifiers {
+    pub unique: bool,
+    pub index: bool,
+    pub default: Option<String>,
+    /// Comparisons the column has to satisfy, for example `>= 0`
+    pub checks: Vec<String>,
+}
+
+/// Attributes separated by commas, commas inside of brackets belong to the data type
+#[derive(Debug, Clone)]
+pub struct Attributes(pub Vec<Attribute>);
+
+impl FromClap for Attributes {
+    fn from_clap(attributes: &str) -> Result<Self> {
+        let mut res = vec![];
+        let mut depth = 0;
+        let mut start = 0;
+        for (i, c) in attributes.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    res.push(Attribute::from_clap(&attributes[start..i])?);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        res.push(Attribute::from_clap(&attributes[start..])?);
+        Ok(Attributes(res))
+    }
 }
 
 impl FromClap for Attribute {
     fn from_clap(attribute: &str) -> Result<Self> {
-        let (name, mut data_type_definition) = attribute.split_once(':').unwrap();
+        let (name, definition) = attribute
+            .split_once(':')
+            .context("Attributes are written as {name}:{type}")?;
+
+        let mut depth = 0;
+        let modifiers_start = definition
+            .char_indices()
+            .find(|(_, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0 && MODIFIERS.contains(c)
+            })
+            .map_or(definition.len(), |(i, _)| i);
+        let (mut data_type_definition, mut modifiers) = definition.split_at(modifiers_start);
+
+        // The question mark can follow the data type or the modifiers
         let mut required = true;
-        if data_type_definition.ends_with('?') {
+        if let Some(definition) = data_type_definition.strip_suffix('?') {
             required = false;
-            data_type_definition = &data_type_definition[..data_type_definition.len() - 1];
+            data_type_definition = definition;
+        } else if let Some(definition) = modifiers.strip_suffix('?') {
+            required = false;
+            modifiers = definition;
         }
-        let data_type = data_type_definition.parse()?;
+
+        let reference = Reference::parse(data_type_definition, !required)?;
+        let data_type = match reference {
+            // Set to the id type of the referenced table by `resolve`
+            Some(_) => DataType::Integer,
+            None => data_type_definition.parse()?,
+        };
 
         Ok(Attribute {
             name: name.to_string(),
             data_type,
             optional: !required,
+            reference,
+            modifiers: modifiers.parse()?,
         })
     }
 }
@@ -42,7 +115,11 @@ impl FromTerm<Vec<Attribute>> for Attribute {
             }
 
             for attribute in attributes.iter() {
-                let mut res = format!("{}: {}", style(&attribute.name).cyan(), attribute.data_type);
+                let mut res = match &attribute.reference {
+                    Some(reference) => format!("{}: {}", style(&attribute.name).cyan(), reference),
+                    None => format!("{}: {}", style(&attribute.name).cyan(), attribute.data_type),
+                };
+                res.push_str(&attribute.modifiers.to_string());
                 if attribute.optional {
                     res.push_str(" (optional)");
                 }
@@ -62,24 +139,94 @@ impl FromTerm<Vec<Attribute>> for Attribute {
 }
 
 impl Attribute {
+    /// Fills in the parts of the data type that depend on the generated table.
+    /// References get the id type of the referenced table and enums are named after the column.
+    pub fn resolve(mut self, table: &str, id: &IDType, config: &Config) -> Result<Self> {
+        if let Some(reference) = &self.reference {
+            self.data_type = reference.data_type(table, id, config)?;
+        }
+        if let DataType::Enum(enum_type) = &mut self.data_type {
+            enum_type.name = format!("{}_{}", table, self.name).to_case(Case::Snake);
+        }
+        Ok(self)
+    }
+
+    pub fn enum_type(&self) -> Option<&EnumType> {
+        match &self.data_type {
+            DataType::Enum(enum_type) => Some(enum_type),
+            _ => None,
+        }
+    }
+
+    pub fn foreign_key(&self) -> Option<ForeignKey> {
+        self.reference
+            .as_ref()
+            .map(|reference| ForeignKey::new(&self.name, reference))
+    }
+
     fn get_attribute(term: &Term, theme: &ColorfulTheme) -> Result<Attribute> {
         let name: String = Input::with_theme(theme)
             .with_prompt("Set your field name")
             .interact_on(term)
             .context("Failed to get field name")?;
 
-        let data_type: DataType = FuzzySelect::with_theme(theme)
+        // References are picked like a data type, their type follows the referenced id
+        let mut items = DataType::VALUES.to_vec();
+        items.push("reference");
+        let selected = FuzzySelect::with_theme(theme)
             .with_prompt("Pick a data type")
-            .items(&DataType::VALUES)
+            .items(&items)
             .interact_on(term)
-            .context("Failed to get data type")?
-            .try_into()?;
+            .context("Failed to get data type")?;
 
         let optional = Confirm::with_theme(theme)
             .with_prompt("Is this field optional?")
             .interact_on(term)
             .context("Failed to get optional")?;
 
+        if selected == DataType::VALUES.len() {
+            return Ok(Attribute {
+                name,
+                data_type: DataType::Integer,
+                optional,
+                reference: Some(Reference::from_term(term, theme)?),
+                modifiers: Self::get_modifiers(term, theme)?,
+            });
+        }
+        let data_type = match selected.try_into()? {
+            DataType::Array(_) => {
+                // Arrays of arrays or enums can't be picked as elements
+                let element_types = &DataType::VALUES[..DataType::VALUES.len() - 2];
+                let element_type = FuzzySelect::with_theme(theme)
+                    .with_prompt("Pick the data type of the elements")
+                    .items(element_types)
+                    .interact_on(term)
+                    .context("Failed to get element data type")?
+                    .try_into()?;
+                DataType::Array(Box::new(Self::get_data_type_arguments(
+                    element_type,
+                    term,
+                    theme,
+                )?))
+            }
+            data_type => Self::get_data_type_arguments(data_type, term, theme)?,
+        };
+
+        Ok(Attribute {
+            name,
+            data_type,
+            optional,
+            reference: None,
+            modifiers: Self::get_modifiers(term, theme)?,
+        })
+    }
+
+    /// Asks for the lengths and values some data types are defined with
+    fn get_data_type_arguments(
+        data_type: DataType,
+        term: &Term,
+        theme: &ColorfulTheme,
+    ) -> Result<DataType> {
         let data_type = match data_type {
             DataType::Numeric(_, _) => {
                 let precision: u32 = Input::with_theme(theme)
@@ -104,13 +251,157 @@ impl Attribute {
                     _ => data_type,
                 }
             }
+            DataType::Enum(_) => {
+                let values: String = Input::with_theme(theme)
+                    .with_prompt("Set values (comma separated)")
+                    .interact_on(term)
+                    .context("Failed to get enum values")?;
+
+                format!("enum({})", values).parse()?
+            }
             _ => data_type,
         };
+        Ok(data_type)
+    }
 
-        Ok(Attribute {
-            name,
-            data_type,
-            optional,
-        })
+    fn get_modifiers(term: &Term, theme: &ColorfulTheme) -> Result<Modifiers> {
+        let modifiers: String = Input::with_theme(theme)
+            .with_prompt("Set modifiers like !unique, #index, =default or >=0 (optional)")
+            .allow_empty(true)
+            .interact_on(term)
+            .context("Failed to get modifiers")?;
+
+        modifiers.parse()
+    }
+}
+
+impl FromStr for Modifiers {
+    type Err = anyhow::Error;
+
+    fn from_str(modifiers: &str) -> Result<Self> {
+        let mut res = Modifiers::default();
+        let mut rest = modifiers.trim();
+
+        while !rest.is_empty() {
+            let operator_length = match rest.starts_with(">=") || rest.starts_with("<=") {
+                true => 2,
+                false => 1,
+            };
+            let (operator, value) = rest.split_at(operator_length);
+            let (value, next) = value.split_at(value.find(MODIFIERS).unwrap_or(value.len()));
+            rest = next;
+
+            match (operator, value) {
+                ("!", "unique") => res.unique = true,
+                ("#", "index") => res.index = true,
+                (_, "") => anyhow::bail!("Missing value for modifier {}", operator),
+                ("=", _) => res.default = Some(value.to_string()),
+                (">=" | "<=" | ">" | "<", _) => res.checks.push(format!("{} {}", operator, value)),
+                _ => anyhow::bail!("Invalid modifier {}{}", operator, value),
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Writes the attribute in the syntax it is parsed from
+impl Display for Attribute {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.reference {
+            Some(reference) => write!(fmt, "{}:{}", self.name, reference)?,
+            None => write!(fmt, "{}:{}", self.name, self.data_type)?,
+        }
+        write!(fmt, "{}", self.modifiers)?;
+        if self.optional {
+            write!(fmt, "?")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Modifiers {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.unique {
+            write!(fmt, "!unique")?;
+        }
+        if self.index {
+            write!(fmt, "#index")?;
+        }
+        if let Some(default) = &self.default {
+            write!(fmt, "={}", default)?;
+        }
+        for check in &self.checks {
+            write!(fmt, "{}", check.replace(' ', ""))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::transformers::{DataTypeTransformer, PostgresMigration, SqlxStruct};
+    use super::*;
+    use crate::config::{DatabaseType, DecimalCrate};
+
+    #[test]
+    fn test_attribute_modifiers() {
+        let attribute = Attribute::from_clap("email:varChar(255)!unique#index?").unwrap();
+        assert!(matches!(attribute.data_type, DataType::VarChar(255)));
+        assert!(attribute.optional);
+        assert!(attribute.modifiers.unique);
+        assert!(attribute.modifiers.index);
+
+        let attribute = Attribute::from_clap("price:numeric(10,2)>=0<1000").unwrap();
+        assert!(matches!(attribute.data_type, DataType::Numeric(10, 2)));
+        assert!(!attribute.optional);
+        assert_eq!(attribute.modifiers.checks, vec![">= 0", "< 1000"]);
+        assert_eq!(attribute.to_string(), "price:numeric(10,2)>=0<1000");
+
+        let attribute = Attribute::from_clap("status:text?=draft").unwrap();
+        assert!(attribute.optional);
+        assert_eq!(attribute.modifiers.default.as_deref(), Some("draft"));
+
+        assert!(Attribute::from_clap("age:int#unique").is_err());
+        assert!(Attribute::from_clap("age:int=").is_err());
+    }
+
+    #[test]
+    fn test_attribute_list() {
+        let attributes = Attributes::from_clap("title:text,price:numeric(10,2)>=0,body:text?")
+            .unwrap()
+            .0;
+        let names = attributes
+            .iter()
+            .map(|attribute| attribute.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["title", "price", "body"]);
+    }
+
+    #[test]
+    fn test_attribute_array() {
+        let attribute = Attribute::from_clap("tags:varChar(32)[]?").unwrap();
+        assert!(attribute.optional);
+        match &attribute.data_type {
+            DataType::Array(element_type) => {
+                assert!(matches!(**element_type, DataType::VarChar(32)))
+            }
+            _ => panic!("Expected an array"),
+        }
+        assert_eq!(attribute.data_type.to_string(), "varChar(32)[]");
+        assert_eq!(
+            PostgresMigration {}.get_optional_row(&attribute.data_type, &attribute.name),
+            "tags VARCHAR(32)[]"
+        );
+        assert_eq!(
+            SqlxStruct {
+                database_type: DatabaseType::PostgreSQL,
+                decimal: DecimalCrate::RustDecimal,
+            }
+            .get_row(&attribute.data_type, &attribute.name),
+            "tags: Vec<String>"
+        );
+
+        assert!(Attribute::from_clap("tags:text[][]").is_err());
+        assert!(Attribute::from_clap("tags:enum(a,b)[]").is_err());
     }
 }
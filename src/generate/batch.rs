@@ -0,0 +1,193 @@
+use super::conflict::OnConflict;
+use super::options::GenerateOptions;
+use super::resource::Resource;
+use super::{check_id, check_options, generate_resource, ResourceArgs};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::time::Duration;
+
+/// Resources that are generated in one run, written like the resources in schmiede.toml
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ResourceFile {
+    resources: Vec<Resource>,
+}
+
+impl From<Resource> for ResourceArgs {
+    fn from(resource: Resource) -> Self {
+        Self {
+            name: resource.name,
+            id: Some(resource.id),
+            attributes: Some(resource.attributes),
+            operations: resource.operations,
+            options: resource.options,
+        }
+    }
+}
+
+pub fn generate_from_file(path: &Path, on_conflict: OnConflict, config: &mut Config) -> Result<()> {
+    let file = read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+    let resources = order_resources(parse_resources(path, &file)?)?;
+    for resource in &resources {
+        if resource.options.is_empty() {
+            anyhow::bail!("No options selected for {}", resource.name);
+        }
+        if resource.options.contains(&GenerateOptions::Routes) && resource.operations.is_none() {
+            anyhow::bail!("No operations selected for the routes of {}", resource.name);
+        }
+        check_options(config, &resource.options)?;
+        check_id(config, &Some(resource.id.clone()))?;
+    }
+
+    // The migrations are a second apart, so they run in the order the resources are generated
+    let start = Utc::now();
+    for (i, resource) in resources.into_iter().enumerate() {
+        let name = resource.name.clone();
+        generate_resource(
+            resource.into(),
+            start + Duration::from_secs(i as u64),
+            on_conflict,
+            config,
+        )
+        .context(format!("Failed to generate {}", name))?;
+    }
+    Ok(())
+}
+
+/// Parses the definitions as yaml or toml, depending on the extension of the file
+fn parse_resources(path: &Path, definitions: &str) -> Result<Vec<Resource>> {
+    let file: ResourceFile = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("yaml" | "yml") => {
+            serde_yaml::from_str(definitions).context("Failed to parse resource definitions")?
+        }
+        Some("toml") | None => {
+            toml::from_str(definitions).context("Failed to parse resource definitions")?
+        }
+        Some(extension) => anyhow::bail!(
+            "Resource definitions have to be written in toml or yaml, not {}",
+            extension
+        ),
+    };
+    Ok(file.resources)
+}
+
+/// Orders the resources so that referenced tables are generated first,
+/// otherwise the order of the file is kept
+fn order_resources(mut resources: Vec<Resource>) -> Result<Vec<Resource>> {
+    let mut ordered = vec![];
+    while !resources.is_empty() {
+        let pos = resources
+            .iter()
+            .position(|resource| {
+                resource
+                    .attributes
+                    .iter()
+                    .filter_map(|attribute| attribute.reference.as_ref())
+                    .all(|reference| {
+                        resource.is_table(&reference.name)
+                            || !resources
+                                .iter()
+                                .any(|other| other.is_table(&reference.name))
+                    })
+            })
+            .context(format!(
+                "Failed to order resources, {} reference each other",
+                resources
+                    .iter()
+                    .map(|resource| resource.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))?;
+        ordered.push(resources.remove(pos));
+    }
+    Ok(ordered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources(definitions: &str) -> Vec<Resource> {
+        toml::from_str::<ResourceFile>(definitions)
+            .unwrap()
+            .resources
+    }
+
+    #[test]
+    fn test_order_resources() {
+        let definitions = r#"
+[[resources]]
+name = "comment"
+id = "int"
+attributes = ["post:ref(post)", "author:belongs_to(user)"]
+
+[[resources]]
+name = "post"
+id = "uuid"
+attributes = ["title:text", "author:ref(user)", "parent:ref(post)?"]
+
+[[resources]]
+name = "user"
+id = "int"
+attributes = ["name:text", "team:ref(team)?"]
+"#;
+        let ordered = order_resources(resources(definitions)).unwrap();
+        let names = ordered
+            .iter()
+            .map(|resource| resource.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["user", "post", "comment"]);
+
+        let definitions = r#"
+[[resources]]
+name = "post"
+id = "int"
+attributes = ["author:ref(user)"]
+
+[[resources]]
+name = "user"
+id = "int"
+attributes = ["favorite:ref(post)"]
+"#;
+        assert!(order_resources(resources(definitions)).is_err());
+    }
+
+    #[test]
+    fn test_parse_yaml_resources() {
+        let definitions = r#"
+resources:
+  - name: post
+    id: uuid
+    attributes: ["title:text", "author:ref(user)"]
+    operations: crud
+    options: [sql, struct, routes]
+  - name: user
+    id: int
+    attributes:
+      - name:text
+"#;
+        let yaml = parse_resources(Path::new("resources.yaml"), definitions).unwrap();
+        let toml = resources(
+            r#"
+[[resources]]
+name = "post"
+id = "uuid"
+attributes = ["title:text", "author:ref(user)"]
+operations = "crud"
+options = ["sql", "struct", "routes"]
+
+[[resources]]
+name = "user"
+id = "int"
+attributes = ["name:text"]
+"#,
+        );
+        assert_eq!(format!("{:?}", yaml), format!("{:?}", toml));
+        assert!(parse_resources(Path::new("resources.yml"), definitions).is_ok());
+        assert!(parse_resources(Path::new("resources.json"), definitions).is_err());
+    }
+}
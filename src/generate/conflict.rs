@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use console::{style, Term};
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+/// What happens when a generated file or definition already exists
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OnConflict {
+    /// Keep the existing file
+    Skip,
+    /// Replace the existing file with the generated one
+    Overwrite,
+    /// Stop with an error
+    #[default]
+    Fail,
+    /// Ask for every file
+    Prompt,
+}
+
+impl OnConflict {
+    /// Whether the existing file should be replaced, `Fail` returns an error instead
+    pub fn overwrite(&self, existing: &str) -> Result<bool> {
+        match self {
+            OnConflict::Skip => Ok(false),
+            OnConflict::Overwrite => Ok(true),
+            OnConflict::Fail => anyhow::bail!(
+                "{} already exists, use --on-conflict to skip or overwrite it",
+                existing
+            ),
+            OnConflict::Prompt => Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("{} already exists, overwrite it?", existing))
+                .default(false)
+                .interact_on(&Term::stderr())
+                .context("Failed to read answer"),
+        }
+    }
+}
+
+/// What happened to a generated file
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Created,
+    Skipped,
+    Overwritten,
+}
+
+impl Outcome {
+    pub fn report(&self, file: &str) {
+        let outcome = match self {
+            Outcome::Created => style("created").green(),
+            Outcome::Skipped => style("skipped").yellow(),
+            Outcome::Overwritten => style("overwritten").cyan(),
+        };
+        println!("{:>11} {}", outcome.bold(), file);
+    }
+}
@@ -2,7 +2,7 @@ use super::{FromClap, FromTerm};
 use anyhow::{Context, Result};
 use console::Term;
 use dialoguer::{theme::ColorfulTheme, MultiSelect};
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 #[derive(Clone, Debug)]
 pub enum CrudOperations {
@@ -18,6 +18,15 @@ pub enum SpecificOperation {
     Delete,
 }
 
+impl CrudOperations {
+    pub fn contains(&self, operation: &SpecificOperation) -> bool {
+        match self {
+            CrudOperations::All => true,
+            CrudOperations::Specific(operations) => operations.contains(operation),
+        }
+    }
+}
+
 impl FromClap for CrudOperations {
     fn from_clap(operations: &str) -> Result<Self> {
         if Self::from_str(operations).is_ok() {
@@ -92,13 +101,29 @@ impl FromStr for CrudOperations {
     }
 }
 
+/// Writes the operations in the syntax they are parsed from
+impl Display for CrudOperations {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CrudOperations::All => write!(fmt, "all"),
+            CrudOperations::Specific(operations) => {
+                let operations = operations
+                    .iter()
+                    .map(|operation| operation.to_string())
+                    .collect::<Vec<_>>();
+                write!(fmt, "{}", operations.join(","))
+            }
+        }
+    }
+}
+
 impl SpecificOperation {
     const VALUES: [&'static str; 4] = ["create", "read", "update", "delete"];
 
     fn check_duplicate_values(values: &[SpecificOperation]) -> bool {
         let mut found = vec![];
         for operation in values {
-            if found.iter().any(|o| *o == operation) {
+            if found.contains(&operation) {
                 return true;
             }
             found.push(operation);
@@ -121,6 +146,17 @@ impl TryFrom<usize> for SpecificOperation {
     }
 }
 
+impl Display for SpecificOperation {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SpecificOperation::Create => write!(fmt, "create"),
+            SpecificOperation::Read => write!(fmt, "read"),
+            SpecificOperation::Update => write!(fmt, "update"),
+            SpecificOperation::Delete => write!(fmt, "delete"),
+        }
+    }
+}
+
 impl FromStr for SpecificOperation {
     type Err = anyhow::Error;
 
@@ -4,9 +4,11 @@ use clap::ValueEnum;
 use console::Term;
 use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Select};
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IDType {
     Uuid,
     Int,
@@ -15,6 +17,15 @@ pub enum IDType {
 
 impl IDType {
     const VALUES: [&'static str; 3] = ["uuid", "int", "none"];
+
+    /// Rust type of the id column, empty if there is none
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            IDType::Uuid => "uuid::Uuid",
+            IDType::Int => "i32",
+            IDType::None => "",
+        }
+    }
 }
 
 impl FromTerm<Self> for IDType {
@@ -75,10 +86,52 @@ pub enum DataType {
     // More specialized
     Jsonb,
     Uuid,
+    Enum(EnumType),
+    Array(Box<DataType>), // Array(element type), only supported by PostgreSQL
+}
+
+/// Values of an enum column, the type is named after its table and column
+#[derive(Debug, Clone)]
+pub struct EnumType {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl EnumType {
+    pub fn new(values: &[&str]) -> Self {
+        Self {
+            name: String::new(),
+            values: values
+                .iter()
+                .map(|value| value.trim().to_case(Case::Snake))
+                .collect(),
+        }
+    }
+
+    pub fn rust_name(&self) -> String {
+        self.name.to_case(Case::Pascal)
+    }
+
+    /// Rust variants with the value they are stored as
+    pub fn variants(&self) -> Vec<(String, &String)> {
+        self.values
+            .iter()
+            .map(|value| (value.to_case(Case::Pascal), value))
+            .collect()
+    }
+
+    /// Values as quoted SQL strings, for example `'draft', 'published'`
+    pub fn sql_values(&self) -> String {
+        self.values
+            .iter()
+            .map(|value| format!("'{}'", value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 impl DataType {
-    pub const VALUES: [&'static str; 19] = [
+    pub const VALUES: [&'static str; 21] = [
         "bool",
         "smallInt",
         "int",
@@ -98,7 +151,13 @@ impl DataType {
         "interval",
         "jsonb",
         "uuid",
+        "enum",
+        "array",
     ];
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, DataType::Array(_))
+    }
 }
 
 impl Display for DataType {
@@ -110,9 +169,13 @@ impl Display for DataType {
             DataType::BigInt => write!(fmt, "bigInt"),
             DataType::Real => write!(fmt, "real"),
             DataType::DoublePrecision => write!(fmt, "doublePrecision"),
-            DataType::Numeric(_, _) => write!(fmt, "numeric"),
-            DataType::Char(_) => write!(fmt, "char"),
-            DataType::VarChar(_) => write!(fmt, "varChar",),
+            DataType::Numeric(0, _) => write!(fmt, "numeric"),
+            DataType::Numeric(precision, 0) => write!(fmt, "numeric({})", precision),
+            DataType::Numeric(precision, scale) => write!(fmt, "numeric({},{})", precision, scale),
+            DataType::Char(0) => write!(fmt, "char"),
+            DataType::Char(length) => write!(fmt, "char({})", length),
+            DataType::VarChar(0) => write!(fmt, "varChar"),
+            DataType::VarChar(length) => write!(fmt, "varChar({})", length),
             DataType::Text => write!(fmt, "text"),
             DataType::Bytea => write!(fmt, "bytea"),
             DataType::Timestamp => write!(fmt, "timestamp"),
@@ -123,6 +186,8 @@ impl Display for DataType {
             DataType::Interval => write!(fmt, "interval"),
             DataType::Jsonb => write!(fmt, "jsonb"),
             DataType::Uuid => write!(fmt, "uuid"),
+            DataType::Enum(enum_type) => write!(fmt, "enum({})", enum_type.values.join(",")),
+            DataType::Array(element_type) => write!(fmt, "{}[]", element_type),
         }
     }
 }
@@ -131,6 +196,45 @@ impl FromStr for DataType {
     type Err = anyhow::Error;
 
     fn from_str(str: &str) -> Result<Self> {
+        // Arrays are written with brackets after the element type, for example text[]
+        if let Some(element_type) = str.strip_suffix("[]") {
+            let element_type: DataType = element_type.parse()?;
+            if matches!(element_type, DataType::Array(_) | DataType::Enum(_)) {
+                anyhow::bail!("Arrays of arrays or enums are not supported");
+            }
+            return Ok(DataType::Array(Box::new(element_type)));
+        }
+
+        // Lengths are given in brackets, for example varChar(255) or numeric(10,2)
+        if let Some(values) = str
+            .strip_prefix("enum(")
+            .and_then(|values| values.strip_suffix(')'))
+        {
+            let values = values.split(',').collect::<Vec<_>>();
+            if values.iter().any(|value| value.trim().is_empty()) {
+                anyhow::bail!("Invalid value in enum");
+            }
+            return Ok(DataType::Enum(EnumType::new(&values)));
+        }
+
+        if let Some((data_type, arguments)) = str.split_once('(') {
+            let arguments = arguments
+                .strip_suffix(')')
+                .context("Missing closing bracket in data type")?
+                .split(',')
+                .map(|argument| argument.trim().parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+                .context("Invalid length in data type")?;
+
+            return match (data_type.to_case(Case::Camel).as_str(), &arguments[..]) {
+                ("numeric", [precision, scale]) => Ok(DataType::Numeric(*precision, *scale)),
+                ("numeric", [precision]) => Ok(DataType::Numeric(*precision, 0)),
+                ("char", [length]) => Ok(DataType::Char(*length)),
+                ("varChar", [length]) => Ok(DataType::VarChar(*length)),
+                _ => Err(anyhow::anyhow!("Invalid data type")),
+            };
+        }
+
         match str.to_case(Case::Camel).as_str() {
             "bool" => Ok(DataType::Boolean),
             "smallInt" => Ok(DataType::SmallInt),
@@ -180,6 +284,8 @@ impl TryFrom<usize> for DataType {
             16 => Ok(DataType::Interval),
             17 => Ok(DataType::Jsonb),
             18 => Ok(DataType::Uuid),
+            19 => Ok(DataType::Enum(EnumType::new(&[]))),
+            20 => Ok(DataType::Array(Box::new(DataType::Text))),
             _ => anyhow::bail!("Failed to convert data type from usize"),
         }
     }
@@ -0,0 +1,136 @@
+use super::data_types::{EnumType, IDType};
+use crate::add::database::diesel::DIESEL_VERSION;
+use crate::add::Dependency;
+use crate::config::{Database, DatabaseDriver, DatabaseType, DecimalCrate};
+
+/// Crates and driver features the generated models need, found through the rust types of their rows
+pub fn get_dependencies(
+    rows: &[String],
+    id: &IDType,
+    enums: &[EnumType],
+    uses_arrays: bool,
+    database: &Database,
+) -> Vec<Dependency> {
+    let uses = |path: &str| rows.iter().any(|row| row.contains(path));
+    let driver = &database.database_driver;
+    let database_type = &database.database_type;
+
+    // Every model has timestamps
+    let mut dependencies = vec![("chrono", "0.4", Some(vec!["serde"]))];
+    let mut features = vec![];
+    match driver {
+        DatabaseDriver::Sqlx | DatabaseDriver::Diesel => features.push("chrono"),
+        DatabaseDriver::SeaOrm => {}
+    }
+
+    if matches!(id, IDType::Uuid) || uses("uuid::Uuid") {
+        dependencies.push(("uuid", "1", Some(vec!["serde", "v4"])));
+        match driver {
+            DatabaseDriver::Sqlx | DatabaseDriver::Diesel => features.push("uuid"),
+            DatabaseDriver::SeaOrm => {}
+        }
+    }
+
+    if uses("serde_json::") {
+        dependencies.push(("serde_json", "1", None));
+        match driver {
+            DatabaseDriver::Sqlx => features.push("json"),
+            DatabaseDriver::Diesel => features.push("serde_json"),
+            DatabaseDriver::SeaOrm => {}
+        }
+    }
+
+    if uses(database.decimal.rust_type()) {
+        match (&database.decimal, driver, database_type) {
+            (DecimalCrate::RustDecimal, DatabaseDriver::Diesel, DatabaseType::MySQL) => {
+                dependencies.push(("rust_decimal", "1", Some(vec!["db-diesel2-mysql"])))
+            }
+            (DecimalCrate::RustDecimal, DatabaseDriver::Diesel, _) => {
+                dependencies.push(("rust_decimal", "1", Some(vec!["db-diesel2-postgres"])))
+            }
+            (DecimalCrate::RustDecimal, _, _) => dependencies.push(("rust_decimal", "1", None)),
+            // Has to match the version sqlx and sea-orm are built with
+            (DecimalCrate::BigDecimal, DatabaseDriver::Diesel, _) => {
+                dependencies.push(("bigdecimal", "0.4", Some(vec!["serde"])))
+            }
+            (DecimalCrate::BigDecimal, _, _) => {
+                dependencies.push(("bigdecimal", "0.3", Some(vec!["serde"])))
+            }
+        }
+        match (&database.decimal, driver) {
+            (DecimalCrate::RustDecimal, DatabaseDriver::Sqlx) => features.push("rust_decimal"),
+            (DecimalCrate::BigDecimal, DatabaseDriver::Sqlx) => features.push("bigdecimal"),
+            (DecimalCrate::BigDecimal, DatabaseDriver::Diesel) => features.push("numeric"),
+            _ => {}
+        }
+    }
+
+    // Binary columns are Vec<u8> too, only postgres arrays need the feature
+    if uses_arrays
+        && driver == &DatabaseDriver::SeaOrm
+        && database_type == &DatabaseType::PostgreSQL
+    {
+        features.push("postgres-array");
+    }
+
+    if !enums.is_empty() && driver == &DatabaseDriver::Diesel {
+        let backend = match database_type {
+            DatabaseType::PostgreSQL => "postgres",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::Sqlite => "sqlite",
+        };
+        dependencies.push(("diesel-derive-enum", "2.1", Some(vec![backend])));
+    }
+
+    if !features.is_empty() {
+        dependencies.push(match driver {
+            DatabaseDriver::Sqlx => ("sqlx", "0.7.4", Some(features)),
+            DatabaseDriver::Diesel => ("diesel", DIESEL_VERSION, Some(features)),
+            DatabaseDriver::SeaOrm => ("sea-orm", "0.12.15", Some(features)),
+        });
+    }
+    dependencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependencies_of_rows() {
+        let database = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::Sqlx);
+        let rows = vec![
+            "author: uuid::Uuid".to_string(),
+            "price: Option<rust_decimal::Decimal>".to_string(),
+        ];
+        let dependencies = get_dependencies(&rows, &IDType::Int, &[], false, &database);
+        let names = dependencies
+            .iter()
+            .map(|(name, _, _)| *name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["chrono", "uuid", "rust_decimal", "sqlx"]);
+        assert_eq!(
+            dependencies[3].2,
+            Some(vec!["chrono", "uuid", "rust_decimal"])
+        );
+
+        let database = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::SeaOrm);
+        let dependencies = get_dependencies(&[], &IDType::Int, &[], false, &database);
+        assert_eq!(dependencies, vec![("chrono", "0.4", Some(vec!["serde"]))]);
+    }
+
+    #[test]
+    fn test_sea_orm_array_dependencies() {
+        let database = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::SeaOrm);
+        let rows = vec!["avatar: Vec<u8>".to_string()];
+        let dependencies = get_dependencies(&rows, &IDType::Int, &[], false, &database);
+        assert_eq!(dependencies, vec![("chrono", "0.4", Some(vec!["serde"]))]);
+
+        let rows = vec!["tags: Vec<String>".to_string()];
+        let dependencies = get_dependencies(&rows, &IDType::Int, &[], true, &database);
+        assert_eq!(
+            dependencies[1],
+            ("sea-orm", "0.12.15", Some(vec!["postgres-array"]))
+        );
+    }
+}
@@ -1,25 +1,34 @@
+use super::conflict::{OnConflict, Outcome};
 use super::template::{
-    AxumDieselTemplate, AxumSqlxTemplate, DieselDownTemplate, DieselModelTemplate,
-    DieselUpTemplate, SqlxDownTemplate, SqlxModelTemplate, SqlxUpTemplate,
+    ActixDieselTemplate, ActixSeaOrmTemplate, ActixSqlxTemplate, AxumDieselTemplate,
+    AxumSeaOrmTemplate, AxumSqlxTemplate, DieselDownTemplate, DieselModelTemplate,
+    DieselSchemaTemplate, DieselUpTemplate, SeaOrmMigrationTemplate, SeaOrmModelTemplate,
+    SqlxDownTemplate, SqlxModelTemplate, SqlxUpTemplate,
 };
+use crate::add::RustFile;
+use crate::config::ApiFramework;
+use crate::files::{self, create_dir_all, read_dir, write};
 use anyhow::{Context, Result};
 use askama::Template;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use convert_case::{Case, Casing};
-use std::fs::{create_dir_all, read_to_string, OpenOptions};
-use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub trait Export {
-    fn export(&self) -> Result<()>;
+    /// Writes the rendered template, existing files are handled according to `on_conflict`
+    fn export(&self, on_conflict: OnConflict) -> Result<()>;
 }
 
 impl Export for SqlxModelTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        create_model_file(self.struct_name, &self.render()?.into_bytes())
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_model_file(
+            &format!("pub struct {}", self.struct_name),
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
 }
 
@@ -27,55 +36,76 @@ impl Export for DieselModelTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        create_model_file(self.struct_name, &self.render()?.into_bytes())
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_model_file(
+            &format!("pub struct {}", self.struct_name),
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
 }
 
-fn create_model_file(name: &str, content: &[u8]) -> Result<()> {
-    let file_path = String::from("src/common/models.rs");
-
-    let exists = Path::new(&file_path).exists();
-
-    if !exists {
-        create_dir_all(Path::new(&file_path).parent().unwrap())?;
+impl Export for SeaOrmModelTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_model_file(
+            &format!("pub mod {}", self.name.to_lowercase()),
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
+}
 
-    // Check if the struct already exists
-    let struct_exists = match exists {
-        true => {
-            // Read existing file contents (if it exists)
-            let contents = read_to_string(&file_path)?;
-            contents
-                .lines()
-                .any(|line| line.starts_with(&format!("pub struct {} ", name)))
+fn create_model_file(definition: &str, content: &[u8], on_conflict: OnConflict) -> Result<()> {
+    let file_path = Path::new("src/common/models.rs");
+    let mut models = match files::exists(file_path) {
+        true => RustFile::open(file_path)?,
+        false => {
+            create_dir_all(file_path.parent().unwrap())?;
+            RustFile::new(file_path)
         }
-        false => false,
     };
+    let content = String::from_utf8_lossy(content);
+    let model = format!("{} in {}", definition, file_path.display());
 
-    if !struct_exists {
-        // Append to the file
-        let mut file = OpenOptions::new()
-            .append(true)
-            .create(!exists)
-            .open(&file_path)
-            .context(format!("Failed to create model for {}", name))?;
-
-        file.write_all(b"\n")?; // Add a newline if needed
-        file.write_all(content)?;
-        file.write_all(b"\n")?; // Add a newline if needed
-        Ok(())
-    } else {
-        anyhow::bail!("Struct already exists")
+    // The use declarations are shared with the other models, the items belong to this one
+    let exists = models.has_items(&content)?;
+    if exists {
+        match on_conflict.overwrite(&model)? {
+            true => models.remove_items(&content)?,
+            false => {
+                Outcome::Skipped.report(&model);
+                return Ok(());
+            }
+        }
     }
+
+    models.add_items(&content)?;
+    models
+        .save()
+        .context(format!("Failed to create model for {}", definition))?;
+    match exists {
+        true => Outcome::Overwritten.report(&model),
+        false => Outcome::Created.report(&model),
+    }
+    Ok(())
 }
 
 impl Export for SqlxUpTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        create_migration_file(self.name, false, "up", &self.render()?.into_bytes())
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        export_migration(
+            self.name,
+            self.timestamp,
+            false,
+            "up",
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
 }
 
@@ -83,8 +113,15 @@ impl Export for SqlxDownTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        create_migration_file(self.name, false, "down", &self.render()?.into_bytes())
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        export_migration(
+            self.name,
+            self.timestamp,
+            false,
+            "down",
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
 }
 
@@ -92,8 +129,15 @@ impl Export for DieselUpTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        create_migration_file(self.name, true, "up", &self.render()?.into_bytes())
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        export_migration(
+            self.name,
+            self.timestamp,
+            true,
+            "up",
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
 }
 
@@ -101,19 +145,43 @@ impl Export for DieselDownTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        create_migration_file(self.name, true, "down", &self.render()?.into_bytes())
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        export_migration(
+            self.name,
+            self.timestamp,
+            true,
+            "down",
+            &self.render()?.into_bytes(),
+            on_conflict,
+        )
     }
 }
 
-fn create_migration_file(name: &str, has_dir: bool, ty: &str, content: &[u8]) -> Result<()> {
+/// Creates the migration of a table, an earlier migration of the same name is a conflict
+/// and keeps its timestamp when it is overwritten
+fn export_migration(
+    name: &str,
+    timestamp: DateTime<Utc>,
+    has_dir: bool,
+    ty: &str,
+    content: &[u8],
+    on_conflict: OnConflict,
+) -> Result<()> {
+    let file_path = find_migration(name, has_dir, ty)
+        .unwrap_or_else(|| migration_path(name, timestamp, has_dir, ty));
+    create_migration_file(&file_path, content, on_conflict)
+        .context(format!("Failed to create migration for {}", name))
+}
+
+/// Path of a new migration, diesel keeps the up and down files in a directory
+pub fn migration_path(name: &str, timestamp: DateTime<Utc>, has_dir: bool, ty: &str) -> PathBuf {
     let timestamp_format = match has_dir {
         true => "%Y-%m-%d-%H%M%S",
         false => "%Y%m%d%H%M%S",
     };
-    let timestamp = Utc::now().format(timestamp_format).to_string();
+    let timestamp = timestamp.format(timestamp_format).to_string();
 
-    let file_name = match has_dir {
+    match has_dir {
         true => format!(
             "migrations/{}_{}/{}.sql",
             timestamp,
@@ -126,67 +194,342 @@ fn create_migration_file(name: &str, has_dir: bool, ty: &str, content: &[u8]) ->
             name.to_lowercase(),
             ty
         ),
-    };
-    let file_path = Path::new(&file_name);
-
-    if !file_path.exists() {
-        create_dir_all(file_path.parent().unwrap())?;
     }
+    .into()
+}
 
-    let mut file = OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(file_path)
-        .context(format!("Failed to create migration for {}", name))?;
+/// Earlier migration with the same name, regardless of its timestamp
+fn find_migration(name: &str, has_dir: bool, ty: &str) -> Option<PathBuf> {
+    let name = name.to_lowercase();
+    read_dir("migrations")
+        .unwrap_or_default()
+        .into_iter()
+        .find(|entry| {
+            let file_name = entry
+                .file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().to_string());
+            // Migrations are prefixed by their timestamp
+            let migration = file_name.split_once('_').map_or("", |(_, name)| name);
+            match has_dir {
+                true => migration == name,
+                false => migration == format!("{}.{}.sql", name, ty),
+            }
+        })
+        .map(|entry| match has_dir {
+            true => entry.join(format!("{}.sql", ty)),
+            false => entry,
+        })
+}
+
+pub fn create_migration_file(
+    file_path: &Path,
+    content: &[u8],
+    on_conflict: OnConflict,
+) -> Result<()> {
+    let file = file_path.display().to_string();
+    let exists = files::exists(file_path);
+    match exists {
+        true if on_conflict.overwrite(&file)? => {}
+        true => {
+            Outcome::Skipped.report(&file);
+            return Ok(());
+        }
+        false => create_dir_all(file_path.parent().unwrap())?,
+    }
 
-    file.write_all(content)?;
-    file.write_all(b"\n")?; // Add a newline if needed
+    // Add a newline if needed
+    let content = format!("{}\n", String::from_utf8_lossy(content));
+    write(file_path, content)?;
+    match exists {
+        true => Outcome::Overwritten.report(&file),
+        false => Outcome::Created.report(&file),
+    }
     Ok(())
 }
 
-impl Export for AxumDieselTemplate<'_>
+impl Export for DieselSchemaTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        let file_path = format!("src/routes/{}.rs", self.name.to_case(Case::Snake));
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        let mut schema = RustFile::open(Path::new("src/schema.rs")).context(
+            "Failed to read src/schema.rs, is diesel set up with `schmiede add database`?",
+        )?;
 
-        let mut file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(file_path)
-            .context(format!("Failed to create template for {}", self.name))?;
+        let name = self.name.to_lowercase();
+        let table = format!("table {} in src/schema.rs", name);
+        let exists = schema.tables()?.contains(&name);
+        match exists {
+            true if on_conflict.overwrite(&table)? => remove_table_from_schema(&mut schema, &name)?,
+            true => {
+                Outcome::Skipped.report(&table);
+                return Ok(());
+            }
+            false => {}
+        }
 
-        let contents = self.render()?;
+        let joins = self
+            .foreign_keys
+            .iter()
+            .map(|foreign_key| (foreign_key.table.as_str(), foreign_key.column.as_str()))
+            .collect::<Vec<_>>();
+        add_table_to_schema(
+            &mut schema,
+            &name,
+            &self.render()?,
+            &self.sql_types(),
+            &joins,
+        )?;
+        schema.save()?;
+        match exists {
+            true => Outcome::Overwritten.report(&table),
+            false => Outcome::Created.report(&table),
+        }
+        Ok(())
+    }
+}
 
-        file.write_all(&contents.into_bytes())?;
+/// Removes the `diesel::table!` of the table and its joins to other tables
+fn remove_table_from_schema(schema: &mut RustFile, name: &str) -> Result<()> {
+    let mut items = format!("diesel::table! {{\n    {} {{}}\n}}\n", name);
+    for table in schema.tables()? {
+        items.push_str(&format!("diesel::joinable!({} -> {} (id));\n", name, table));
+    }
+    schema.remove_items(&items)
+}
+
+/// Adds a table to the schema like `diesel print-schema` would, with its enum types in
+/// `sql_types`, joins to the tables it references and the updated list of tables that can
+/// appear in the same query
+fn add_table_to_schema(
+    schema: &mut RustFile,
+    name: &str,
+    table: &str,
+    sql_types: &[String],
+    joins: &[(&str, &str)],
+) -> Result<()> {
+    let mut tables = schema.tables()?;
+    if tables.iter().any(|existing| existing == name) {
+        anyhow::bail!("Table {} already exists in src/schema.rs", name);
+    }
+
+    schema.remove_items("diesel::allow_tables_to_appear_in_same_query!();")?;
+    schema.add_items(table)?;
+    // Enum types of an overwritten table are still declared and left out
+    if !sql_types.is_empty() {
+        schema.add_mod_items("sql_types", &sql_types.join("\n\n"))?;
+    }
+
+    // Joins to the table itself or to tables that aren't in the schema don't compile
+    let joins = joins
+        .iter()
+        .filter(|(table, _)| *table != name && tables.iter().any(|existing| existing == table))
+        .map(|(table, column)| format!("diesel::joinable!({} -> {} ({}));", name, table, column))
+        .collect::<Vec<_>>();
+    schema.add_items(&joins.join("\n"))?;
+
+    tables.push(name.to_string());
+    tables.sort();
+    if tables.len() > 1 {
+        let tables = tables
+            .iter()
+            .map(|table| format!("    {},\n", table))
+            .collect::<String>();
+        schema.add_items(&format!(
+            "diesel::allow_tables_to_appear_in_same_query!(\n{});",
+            tables
+        ))?;
+    }
+    Ok(())
+}
+
+impl Export for SeaOrmMigrationTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        let suffix = format!("_create_{}_table.rs", self.name.to_lowercase());
+        let lib_path = Path::new("migration/src/lib.rs");
+        let mut lib = RustFile::open(lib_path).context(
+            "Failed to read migration/src/lib.rs, is sea-orm set up with `schmiede add database`?",
+        )?;
+
+        // An earlier migration of the table is already registered and keeps its timestamp
+        let existing = read_dir("migration/src")?.into_iter().find(|entry| {
+            entry
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().ends_with(&suffix))
+        });
+        if let Some(file_path) = existing {
+            let file = file_path.display().to_string();
+            if !on_conflict.overwrite(&file)? {
+                Outcome::Skipped.report(&file);
+                return Ok(());
+            }
+            write(&file_path, self.render()?.into_bytes())
+                .context(format!("Failed to create migration for {}", self.name))?;
+            Outcome::Overwritten.report(&file);
+            return Ok(());
+        }
+
+        let module = format!(
+            "m{}_create_{}_table",
+            self.timestamp.format("%Y%m%d_%H%M%S"),
+            self.name.to_lowercase()
+        );
+        let file = format!("migration/src/{}.rs", module);
+        write(&file, self.render()?.into_bytes())
+            .context(format!("Failed to create migration for {}", self.name))?;
+        register_migration(&mut lib, &module)
+            .context("Failed to register migration in migration/src/lib.rs")?;
+        lib.save()?;
+        Outcome::Created.report(&file);
         Ok(())
     }
 }
 
+/// Adds the migration module to the `Migrator` of the sea-orm migration crate
+fn register_migration(lib: &mut RustFile, module: &str) -> Result<()> {
+    lib.add_mod(&format!("mod {};", module))?;
+    lib.add_vec_element("migrations", &format!("Box::new({}::Migration)", module))
+}
+
+impl Export for AxumDieselTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_route_file(self.name, &self.render()?.into_bytes(), on_conflict)?;
+        register_route(self.name, &ApiFramework::Axum)
+    }
+}
+
 impl Export for AxumSqlxTemplate<'_>
 where
     Self: Template,
 {
-    fn export(&self) -> Result<()> {
-        let file_path = format!("src/routes/{}.rs", self.name.to_case(Case::Snake));
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_route_file(self.name, &self.render()?.into_bytes(), on_conflict)?;
+        register_route(self.name, &ApiFramework::Axum)
+    }
+}
 
-        let mut file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(file_path)
-            .context(format!("Failed to create template for {}", self.name))?;
+impl Export for ActixDieselTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_route_file(self.name, &self.render()?.into_bytes(), on_conflict)?;
+        register_route(self.name, &ApiFramework::Actix)
+    }
+}
 
-        let contents = self.render()?;
+impl Export for ActixSqlxTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_route_file(self.name, &self.render()?.into_bytes(), on_conflict)?;
+        register_route(self.name, &ApiFramework::Actix)
+    }
+}
 
-        file.write_all(&contents.into_bytes())?;
-        Ok(())
+impl Export for AxumSeaOrmTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_route_file(self.name, &self.render()?.into_bytes(), on_conflict)?;
+        register_route(self.name, &ApiFramework::Axum)
+    }
+}
+
+impl Export for ActixSeaOrmTemplate<'_>
+where
+    Self: Template,
+{
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
+        create_route_file(self.name, &self.render()?.into_bytes(), on_conflict)?;
+        register_route(self.name, &ApiFramework::Actix)
+    }
+}
+
+fn create_route_file(name: &str, content: &[u8], on_conflict: OnConflict) -> Result<()> {
+    let file_path = format!("src/routes/{}.rs", name.to_case(Case::Snake));
+
+    if !files::exists(Path::new(&file_path).parent().unwrap()) {
+        anyhow::bail!(
+            "Failed to create template for {}, src/routes does not exist",
+            name
+        );
+    }
+    let exists = files::exists(&file_path);
+    if exists && !on_conflict.overwrite(&file_path)? {
+        Outcome::Skipped.report(&file_path);
+        return Ok(());
+    }
+    write(&file_path, content).context(format!("Failed to create template for {}", name))?;
+    match exists {
+        true => Outcome::Overwritten.report(&file_path),
+        false => Outcome::Created.report(&file_path),
+    }
+    Ok(())
+}
+
+/// Declares the module of the routes in src/routes/mod.rs and nests them under the plural of
+/// their name, unless they are already nested
+fn register_route(name: &str, api_framework: &ApiFramework) -> Result<()> {
+    let mod_path = Path::new("src/routes/mod.rs");
+    if !files::exists(mod_path) {
+        return Ok(());
+    }
+    let module = name.to_case(Case::Snake);
+    let path = format!("/{}", pluralize(&name.to_case(Case::Kebab)));
+
+    let mut routes = RustFile::open(mod_path)?;
+    routes.add_mod(&format!("mod {};", module))?;
+    match api_framework {
+        ApiFramework::Axum => routes.chain_method(
+            "routes",
+            "Router::new()",
+            &format!(".nest(\"{}\", {}::routes())", path, module),
+        ),
+        ApiFramework::Actix => routes.chain_method(
+            "routes",
+            "cfg",
+            &format!(
+                ".service(web::scope(\"{}\").configure({}::routes))",
+                path, module
+            ),
+        ),
+    }
+    .context("Failed to register routes in src/routes/mod.rs")?;
+    routes.save()
+}
+
+/// Plural of the last word of a kebab-case name
+fn pluralize(name: &str) -> String {
+    let consonant_y = name.ends_with('y')
+        && !name
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|char| "aeiou".contains(char));
+    if consonant_y {
+        format!("{}ies", &name[..name.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|ending| name.ends_with(ending))
+    {
+        format!("{}es", name)
+    } else {
+        format!("{}s", name)
     }
 }
 
 /*
 impl Export for PageTemplate<'_> {
-    fn export(&self) -> Result<()> {
+    fn export(&self, on_conflict: OnConflict) -> Result<()> {
         let file_path = format!("src/admin/{}.rs", self.function_name);
 
         // Append to the file
@@ -202,3 +545,117 @@ impl Export for PageTemplate<'_> {
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_migration() {
+        let mut lib = RustFile::from_source(
+            r#"pub use sea_orm_migration::prelude::*;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![]
+    }
+}
+"#,
+        );
+        register_migration(&mut lib, "m20240101_000000_create_post_table").unwrap();
+        register_migration(&mut lib, "m20240101_000001_create_user_table").unwrap();
+        register_migration(&mut lib, "m20240101_000001_create_user_table").unwrap();
+
+        let lib = lib.source();
+        assert!(lib.starts_with(
+            "mod m20240101_000000_create_post_table;\nmod m20240101_000001_create_user_table;\n"
+        ));
+        assert!(lib.contains(
+            "        vec![
+            Box::new(m20240101_000000_create_post_table::Migration),
+            Box::new(m20240101_000001_create_user_table::Migration),
+        ]"
+        ));
+    }
+
+    #[test]
+    fn test_add_table_to_schema() {
+        let mut schema = RustFile::from_source("// @generated automatically by Diesel CLI.\n");
+        let user = "diesel::table! {\n    user (id) {\n        id -> Int4,\n    }\n}";
+        add_table_to_schema(&mut schema, "user", user, &[], &[]).unwrap();
+        assert_eq!(
+            schema.source(),
+            "// @generated automatically by Diesel CLI.\n\ndiesel::table! {\n    user (id) {\n        id -> Int4,\n    }\n}\n"
+        );
+
+        let post = "diesel::table! {\n    post (id) {\n        id -> Int4,\n    }\n}";
+        let sql_type = "    pub struct PostStatus;".to_string();
+        add_table_to_schema(
+            &mut schema,
+            "post",
+            post,
+            &[sql_type],
+            &[("user", "author")],
+        )
+        .unwrap();
+        assert!(schema.source().starts_with(
+            "// @generated automatically by Diesel CLI.\n\npub mod sql_types {\n    pub struct PostStatus;\n}\n"
+        ));
+        assert!(schema.source().ends_with(
+            "diesel::joinable!(post -> user (author));\n\ndiesel::allow_tables_to_appear_in_same_query!(\n    post,\n    user,\n);\n"
+        ));
+        assert_eq!(schema.tables().unwrap(), vec!["user", "post"]);
+
+        let comment = "diesel::table! {\n    comment (id) {\n        id -> Int4,\n    }\n}";
+        let sql_types = [
+            "    pub struct PostStatus;".to_string(),
+            "    pub struct CommentState;".to_string(),
+        ];
+        add_table_to_schema(&mut schema, "comment", comment, &sql_types, &[]).unwrap();
+        assert!(schema.source().contains(
+            "pub mod sql_types {\n    pub struct PostStatus;\n\n    pub struct CommentState;\n}"
+        ));
+        assert_eq!(
+            schema
+                .source()
+                .matches("diesel::allow_tables_to_appear_in_same_query!(")
+                .count(),
+            1
+        );
+        assert!(schema
+            .source()
+            .ends_with("(\n    comment,\n    post,\n    user,\n);\n"));
+
+        assert!(add_table_to_schema(&mut schema, "post", post, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_remove_table_from_schema() {
+        let mut schema = RustFile::from_source(
+            "diesel::table! {\n    user (id) {\n        id -> Int4,\n    }\n}\n",
+        );
+        let post = "diesel::table! {\n    post (id) {\n        id -> Int4,\n        author -> Int4,\n    }\n}";
+        add_table_to_schema(&mut schema, "post", post, &[], &[("user", "author")]).unwrap();
+
+        remove_table_from_schema(&mut schema, "post").unwrap();
+        assert_eq!(schema.tables().unwrap(), vec!["user"]);
+        assert!(!schema.source().contains("diesel::joinable!(post"));
+
+        let post = "diesel::table! {\n    post (id) {\n        id -> Int4,\n    }\n}";
+        add_table_to_schema(&mut schema, "post", post, &[], &[]).unwrap();
+        assert_eq!(schema.tables().unwrap(), vec!["user", "post"]);
+        assert!(schema.source().ends_with("(\n    post,\n    user,\n);\n"));
+    }
+
+    #[test]
+    fn test_pluralize() {
+        assert_eq!(pluralize("post"), "posts");
+        assert_eq!(pluralize("blog-category"), "blog-categories");
+        assert_eq!(pluralize("day"), "days");
+        assert_eq!(pluralize("address"), "addresses");
+        assert_eq!(pluralize("batch"), "batches");
+    }
+}
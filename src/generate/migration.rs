@@ -0,0 +1,640 @@
+use super::attribute::Attribute;
+use super::conflict::OnConflict;
+use super::data_types::IDType;
+use super::exporters::{create_migration_file, migration_path};
+use super::options::GenerateOptions;
+use super::{check_diesel_support, get_rows, get_schema_columns, FromClap};
+use crate::add::{FieldChange, RustFile};
+use crate::config::{Config, Database, DatabaseDriver, DatabaseType};
+use crate::files::{self, read_to_string};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Parser;
+use convert_case::{Case, Casing};
+use std::collections::HashMap;
+use std::path::Path;
+
+const MODELS_PATH: &str = "src/common/models.rs";
+const SCHEMA_PATH: &str = "src/schema.rs";
+/// Keywords that end the type in a column definition
+const CONSTRAINTS: [&str; 6] = [
+    " NOT NULL",
+    " UNIQUE",
+    " DEFAULT",
+    " CHECK",
+    " REFERENCES",
+    " PRIMARY KEY",
+];
+
+#[derive(Parser, Debug)]
+pub struct MigrationArgs {
+    /// Name of the table that is altered
+    pub table: String,
+
+    #[arg(value_parser = Change::from_clap, required = true, verbatim_doc_comment)]
+    /// Changes to the columns of the table, written like attributes:
+    /// add:{name}:{type}, remove:{name}, rename:{old}:{new} and change:{name}:{type}.
+    /// Added columns need a default unless they are optional.
+    /// For example: add:views:int=0 rename:body:content change:title:text?
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Change {
+    Add(Attribute),
+    Remove(String),
+    Rename(String, String),
+    Alter(Attribute),
+}
+
+impl FromClap for Change {
+    fn from_clap(change: &str) -> Result<Self> {
+        let (kind, column) = change
+            .split_once(':')
+            .context("Changes are written as {change}:{column}")?;
+
+        match kind {
+            "add" => Ok(Change::Add(Attribute::from_clap(column)?)),
+            "remove" => Ok(Change::Remove(column.to_case(Case::Snake))),
+            "rename" => {
+                let (old, new) = column
+                    .split_once(':')
+                    .context("Renames are written as rename:{old}:{new}")?;
+                Ok(Change::Rename(
+                    old.to_case(Case::Snake),
+                    new.to_case(Case::Snake),
+                ))
+            }
+            "change" => Ok(Change::Alter(Attribute::from_clap(column)?)),
+            _ => anyhow::bail!("Invalid change {}, use add, remove, rename or change", kind),
+        }
+    }
+}
+
+pub fn generate_migration(args: MigrationArgs, config: &mut Config) -> Result<()> {
+    let database = config
+        .database
+        .as_ref()
+        .context("No database configuration found in config file.")?;
+    if database.database_type != DatabaseType::PostgreSQL
+        || database.database_driver == DatabaseDriver::SeaOrm
+    {
+        anyhow::bail!(
+            "Migrations of existing tables are only supported for PostgreSQL with sqlx or diesel."
+        );
+    }
+
+    // Tables are named like in generate, the struct keeps the case of the resource name
+    let table = args.table.to_lowercase();
+    let struct_name = config
+        .resource(&table)
+        .map_or(args.table.as_str(), |resource| resource.name.as_str())
+        .to_case(Case::Pascal);
+    let id = config
+        .resource(&table)
+        .map(|resource| resource.id.clone())
+        .unwrap_or(IDType::Int);
+    let changes = args
+        .changes
+        .into_iter()
+        .map(|change| {
+            Ok(match change {
+                Change::Add(attribute) => Change::Add(attribute.resolve(&table, &id, config)?),
+                Change::Alter(attribute) => Change::Alter(attribute.resolve(&table, &id, config)?),
+                change => change,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if changes.iter().any(|change| match change {
+        Change::Add(attribute) | Change::Alter(attribute) => attribute.enum_type().is_some(),
+        _ => false,
+    }) {
+        anyhow::bail!("Enum columns can only be created with the table.");
+    }
+    check_diesel_support(
+        changes.iter().filter_map(|change| match change {
+            Change::Add(attribute) | Change::Alter(attribute) => Some(attribute),
+            _ => None,
+        }),
+        database,
+    )?;
+
+    let mut columns = find_columns(&read_up_migrations(Path::new("migrations")), &table);
+    // Columns that are missing from the migrations are taken from the registered resource
+    if let Some(resource) = config.resource(&table) {
+        for attribute in &resource.attributes {
+            let attribute = attribute.clone().resolve(&table, &resource.id, config)?;
+            columns
+                .entry(attribute.name.to_case(Case::Snake))
+                .or_insert_with(|| get_column_definition(&attribute, database));
+        }
+    }
+    let (up, down) = get_statements(&table, &changes, &columns, database)?;
+
+    let has_dir = database.database_driver == DatabaseDriver::Diesel;
+    let name = format!("alter_{}", table);
+    let timestamp = Utc::now();
+    // Every change gets a new migration, only a migration of the same second conflicts
+    for (ty, statements) in [("up", up), ("down", down)] {
+        create_migration_file(
+            &migration_path(&name, timestamp, has_dir, ty),
+            statements.join("\n").as_bytes(),
+            OnConflict::Fail,
+        )?;
+    }
+
+    if files::exists(Path::new(MODELS_PATH)) {
+        let mut models = RustFile::open(Path::new(MODELS_PATH))?;
+        let fields = get_field_changes(&changes, |attribute| {
+            get_rows(
+                std::slice::from_ref(attribute),
+                GenerateOptions::Struct,
+                database,
+            )
+            .remove(0)
+        });
+        for name in [
+            struct_name.clone(),
+            format!("New{}", struct_name),
+            format!("Update{}", struct_name),
+        ] {
+            models.edit_struct_fields(&name, &fields)?;
+        }
+        models.edit_associations(&struct_name, &fields)?;
+        models.save()?;
+    }
+
+    if database.database_driver == DatabaseDriver::Diesel {
+        let mut schema = RustFile::open(Path::new(SCHEMA_PATH))?;
+        let columns = get_field_changes(&changes, |attribute| {
+            get_schema_columns(std::slice::from_ref(attribute), &database.database_type).remove(0)
+        });
+        schema.edit_table_columns(&table, &columns)?;
+        schema.edit_joinables(&table, &columns)?;
+        schema.save()?;
+    }
+
+    if let Some(mut resource) = config.resource(&table).cloned() {
+        apply_changes(&mut resource.attributes, &changes);
+        resource.find_migrations(Path::new("."));
+        config.register_resource(resource);
+        config.update_config()?;
+    }
+
+    Ok(())
+}
+
+/// Column definition of an added attribute, with its foreign key
+fn get_column_definition(attribute: &Attribute, database: &Database) -> String {
+    let mut definition = get_rows(
+        std::slice::from_ref(attribute),
+        GenerateOptions::Sql,
+        database,
+    )
+    .remove(0);
+    if let Some(foreign_key) = attribute.foreign_key() {
+        definition.push_str(&format!(
+            " REFERENCES {} (id) ON DELETE {}",
+            foreign_key.table, foreign_key.on_delete
+        ));
+    }
+    definition
+}
+
+/// Applies the changes to the attributes of a registered resource
+fn apply_changes(attributes: &mut Vec<Attribute>, changes: &[Change]) {
+    let is_column = |attribute: &Attribute, name: &str| attribute.name.to_case(Case::Snake) == name;
+    for change in changes {
+        match change {
+            Change::Add(attribute) => attributes.push(attribute.clone()),
+            Change::Remove(name) => attributes.retain(|attribute| !is_column(attribute, name)),
+            Change::Rename(old, new) => {
+                for attribute in attributes
+                    .iter_mut()
+                    .filter(|attribute| is_column(attribute, old))
+                {
+                    attribute.name = new.clone();
+                }
+            }
+            Change::Alter(changed) => {
+                let name = changed.name.to_case(Case::Snake);
+                for attribute in attributes
+                    .iter_mut()
+                    .filter(|attribute| is_column(attribute, &name))
+                {
+                    // The constraints of the column stay in place
+                    attribute.data_type = changed.data_type.clone();
+                    attribute.optional = changed.optional;
+                }
+            }
+        }
+    }
+}
+
+/// Up and down statements of the changes, the down statements undo them in reverse order
+fn get_statements(
+    table: &str,
+    changes: &[Change],
+    columns: &HashMap<String, String>,
+    database: &Database,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut up: Vec<String> = vec![];
+    let mut down = vec![];
+    // Later changes see the columns as the earlier changes left them
+    let mut columns = columns.clone();
+    let alter = format!("ALTER TABLE {} ", table);
+
+    for change in changes {
+        let applied = up.len();
+        let column = |name: &str| {
+            columns.get(name).cloned().context(format!(
+                "Failed to find column {} of table {} in the migrations",
+                name, table
+            ))
+        };
+        match change {
+            Change::Add(attribute) => {
+                // Existing rows would have no value for the column
+                if !attribute.optional && attribute.modifiers.default.is_none() {
+                    anyhow::bail!(
+                        "Column {} is required and needs a default for the existing rows, add one with ={{value}} or make it optional with ?",
+                        attribute.name.to_case(Case::Snake)
+                    );
+                }
+                let name = attribute.name.to_case(Case::Snake);
+                let definition = get_column_definition(attribute, database);
+                up.push(format!("ALTER TABLE {} ADD COLUMN {};", table, definition));
+                down.push(format!("ALTER TABLE {} DROP COLUMN {};", table, name));
+                // Foreign keys get an index like in the migration that creates the table
+                if attribute.modifiers.index || attribute.reference.is_some() {
+                    let index = format!("{}_{}_idx", table, name);
+                    up.push(format!("CREATE INDEX {} ON {} ({});", index, table, name));
+                    down.push(format!("DROP INDEX {};", index));
+                }
+            }
+            Change::Remove(name) => {
+                let definition = column(name)?;
+                up.push(format!("ALTER TABLE {} DROP COLUMN {};", table, name));
+                down.push(format!("ALTER TABLE {} ADD COLUMN {};", table, definition));
+            }
+            Change::Rename(old, new) => {
+                up.push(format!(
+                    "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                    table, old, new
+                ));
+                down.push(format!(
+                    "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                    table, new, old
+                ));
+            }
+            Change::Alter(attribute) => {
+                let name = attribute.name.to_case(Case::Snake);
+                let modifiers = &attribute.modifiers;
+                if modifiers.unique
+                    || modifiers.index
+                    || modifiers.default.is_some()
+                    || !modifiers.checks.is_empty()
+                {
+                    anyhow::bail!(
+                        "The constraints of column {} can't be changed, change only alters its type and whether it is optional",
+                        name
+                    );
+                }
+                let (old_type, old_not_null) = split_definition(&column(&name)?);
+                let definition = get_rows(
+                    std::slice::from_ref(attribute),
+                    GenerateOptions::Sql,
+                    database,
+                )
+                .remove(0);
+                let (new_type, new_not_null) = split_definition(&definition);
+
+                up.extend(alter_column(
+                    table,
+                    &name,
+                    &new_type,
+                    new_not_null,
+                    old_not_null,
+                ));
+                let mut undo = alter_column(table, &name, &old_type, old_not_null, new_not_null);
+                undo.reverse();
+                down.extend(undo);
+            }
+        }
+        for statement in &up[applied..] {
+            apply_alter(&mut columns, statement.trim_start_matches(&alter));
+        }
+    }
+
+    down.reverse();
+    Ok((up, down))
+}
+
+fn alter_column(
+    table: &str,
+    name: &str,
+    sql_type: &str,
+    not_null: bool,
+    was_not_null: bool,
+) -> Vec<String> {
+    let mut statements = vec![format!(
+        "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};",
+        table, name, sql_type, name, sql_type
+    )];
+    match (not_null, was_not_null) {
+        (true, false) => statements.push(format!(
+            "ALTER TABLE {} ALTER COLUMN {} SET NOT NULL;",
+            table, name
+        )),
+        (false, true) => statements.push(format!(
+            "ALTER TABLE {} ALTER COLUMN {} DROP NOT NULL;",
+            table, name
+        )),
+        _ => {}
+    }
+    statements
+}
+
+/// Type and whether the column is required of a definition like `title TEXT NOT NULL`
+fn split_definition(definition: &str) -> (String, bool) {
+    let sql_type = definition.split_once(' ').map_or("", |(_, rest)| rest);
+    let end = CONSTRAINTS
+        .iter()
+        .filter_map(|constraint| sql_type.find(constraint))
+        .min()
+        .unwrap_or(sql_type.len());
+    (
+        sql_type[..end].to_string(),
+        definition.contains(" NOT NULL"),
+    )
+}
+
+/// Contents of the up migrations in the order they run
+fn read_up_migrations(path: &Path) -> Vec<String> {
+    // Diesel keeps every migration in a directory, sqlx keeps them next to each other
+    let migrations = files::read_dir(path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| match entry.join("up.sql") {
+            up if files::exists(&up) => Some(up),
+            _ => entry.to_string_lossy().ends_with("up.sql").then_some(entry),
+        })
+        .collect::<Vec<_>>();
+    migrations
+        .iter()
+        .filter_map(|path| read_to_string(path).ok())
+        .collect()
+}
+
+/// Current definitions of the columns of a table, by replaying the statements of the migrations
+fn find_columns(migrations: &[String], table: &str) -> HashMap<String, String> {
+    let mut columns: HashMap<String, String> = HashMap::new();
+    let create = format!("CREATE TABLE {} (", table);
+    let alter = format!("ALTER TABLE {} ", table);
+
+    for migration in migrations {
+        let mut lines = migration.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            if line == create {
+                for column in lines.by_ref().take_while(|line| *line != ");") {
+                    let column = column.trim_end_matches(',');
+                    // Foreign keys are declared after the columns and moved onto their column
+                    if let Some(foreign_key) = column.strip_prefix("FOREIGN KEY (") {
+                        if let Some((name, constraint)) = foreign_key.split_once(") ") {
+                            if let Some(definition) = columns.get_mut(name) {
+                                definition.push(' ');
+                                definition.push_str(constraint);
+                            }
+                        }
+                    } else if let Some((name, _)) = column.split_once(' ') {
+                        columns.insert(name.to_string(), column.to_string());
+                    }
+                }
+                continue;
+            }
+
+            if let Some(statement) = line.strip_prefix(&alter) {
+                apply_alter(&mut columns, statement);
+            }
+        }
+    }
+    columns
+}
+
+/// Updates the column definitions with an `ALTER TABLE {table} ...` statement, without its prefix
+fn apply_alter(columns: &mut HashMap<String, String>, statement: &str) {
+    let statement = statement.trim_end_matches(';');
+    let words = statement.split(' ').collect::<Vec<_>>();
+    match words[..] {
+        ["ADD", "COLUMN", name, ..] => {
+            let definition = statement.trim_start_matches("ADD COLUMN ");
+            columns.insert(name.to_string(), definition.to_string());
+        }
+        ["DROP", "COLUMN", name] => {
+            columns.remove(name);
+        }
+        ["RENAME", "COLUMN", old, "TO", new] => {
+            if let Some(definition) = columns.remove(old) {
+                let definition = format!("{}{}", new, &definition[old.len()..]);
+                columns.insert(new.to_string(), definition);
+            }
+        }
+        ["ALTER", "COLUMN", name, "TYPE", ..] => {
+            let sql_type = statement
+                .split_once(" TYPE ")
+                .map_or("", |(_, sql_type)| sql_type);
+            let sql_type = sql_type
+                .split_once(" USING ")
+                .map_or(sql_type, |(sql_type, _)| sql_type);
+            if let Some(definition) = columns.get_mut(name) {
+                let (old_type, _) = split_definition(definition);
+                let constraints = &definition[name.len() + 1 + old_type.len()..];
+                *definition = format!("{} {}{}", name, sql_type, constraints);
+            }
+        }
+        ["ALTER", "COLUMN", name, "SET", "NOT", "NULL"] => {
+            if let Some(definition) = columns.get_mut(name) {
+                let (sql_type, _) = split_definition(definition);
+                let constraints = &definition[name.len() + 1 + sql_type.len()..];
+                *definition = format!("{} {} NOT NULL{}", name, sql_type, constraints);
+            }
+        }
+        ["ALTER", "COLUMN", name, "DROP", "NOT", "NULL"] => {
+            if let Some(definition) = columns.get_mut(name) {
+                *definition = definition.replacen(" NOT NULL", "", 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn get_field_changes(changes: &[Change], row: impl Fn(&Attribute) -> String) -> Vec<FieldChange> {
+    changes
+        .iter()
+        .map(|change| match change {
+            Change::Add(attribute) => FieldChange::Add(row(attribute)),
+            Change::Remove(name) => FieldChange::Remove(name.clone()),
+            Change::Rename(old, new) => FieldChange::Rename(old.clone(), new.clone()),
+            Change::Alter(attribute) => {
+                FieldChange::Replace(attribute.name.to_case(Case::Snake), row(attribute))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::attribute::Attributes;
+    use super::*;
+
+    #[test]
+    fn test_find_columns() {
+        let migrations = vec![
+            r#"CREATE TABLE post (
+  id SERIAL PRIMARY KEY,
+  title VARCHAR(100) NOT NULL UNIQUE,
+  body TEXT,
+  author INTEGER NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  FOREIGN KEY (author) REFERENCES users (id) ON DELETE CASCADE
+);"#
+            .to_string(),
+            r#"ALTER TABLE post RENAME COLUMN body TO content;
+ALTER TABLE post ALTER COLUMN content TYPE VARCHAR(255) USING content::VARCHAR(255);
+ALTER TABLE post ALTER COLUMN content SET NOT NULL;
+ALTER TABLE post ADD COLUMN views INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE post DROP COLUMN created_at;"#
+                .to_string(),
+        ];
+        let columns = find_columns(&migrations, "post");
+
+        assert_eq!(columns["title"], "title VARCHAR(100) NOT NULL UNIQUE");
+        assert_eq!(columns["content"], "content VARCHAR(255) NOT NULL");
+        assert_eq!(columns["views"], "views INTEGER NOT NULL DEFAULT 0");
+        assert_eq!(
+            columns["author"],
+            "author INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE"
+        );
+        assert!(!columns.contains_key("body"));
+        assert!(!columns.contains_key("created_at"));
+    }
+
+    #[test]
+    fn test_migration_statements() {
+        let database = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::Sqlx);
+        let columns = HashMap::from([
+            ("body".to_string(), "body TEXT".to_string()),
+            (
+                "title".to_string(),
+                "title VARCHAR(100) NOT NULL".to_string(),
+            ),
+        ]);
+        let changes = ["add:views:int=0", "remove:body", "change:title:text?"]
+            .iter()
+            .map(|change| Change::from_clap(change).unwrap())
+            .collect::<Vec<_>>();
+        let (up, down) = get_statements("post", &changes, &columns, &database).unwrap();
+
+        assert_eq!(
+            up,
+            vec![
+                "ALTER TABLE post ADD COLUMN views INTEGER NOT NULL DEFAULT 0;",
+                "ALTER TABLE post DROP COLUMN body;",
+                "ALTER TABLE post ALTER COLUMN title TYPE TEXT USING title::TEXT;",
+                "ALTER TABLE post ALTER COLUMN title DROP NOT NULL;",
+            ]
+        );
+        assert_eq!(
+            down,
+            vec![
+                "ALTER TABLE post ALTER COLUMN title TYPE VARCHAR(100) USING title::VARCHAR(100);",
+                "ALTER TABLE post ALTER COLUMN title SET NOT NULL;",
+                "ALTER TABLE post ADD COLUMN body TEXT;",
+                "ALTER TABLE post DROP COLUMN views;",
+            ]
+        );
+
+        let changes = ["rename:body:content", "change:content:varChar(255)"]
+            .iter()
+            .map(|change| Change::from_clap(change).unwrap())
+            .collect::<Vec<_>>();
+        let (_, down) = get_statements("post", &changes, &columns, &database).unwrap();
+        assert_eq!(
+            down,
+            vec![
+                "ALTER TABLE post ALTER COLUMN content TYPE TEXT USING content::TEXT;",
+                "ALTER TABLE post ALTER COLUMN content DROP NOT NULL;",
+                "ALTER TABLE post RENAME COLUMN content TO body;",
+            ]
+        );
+
+        let changes = vec![Change::Remove("missing".to_string())];
+        assert!(get_statements("post", &changes, &columns, &database).is_err());
+
+        let changes = vec![Change::from_clap("add:subtitle:text").unwrap()];
+        let err = get_statements("post", &changes, &columns, &database).unwrap_err();
+        assert!(err.to_string().contains("needs a default"));
+        let changes = vec![Change::from_clap("add:subtitle:text?").unwrap()];
+        let (up, _) = get_statements("post", &changes, &columns, &database).unwrap();
+        assert_eq!(up, vec!["ALTER TABLE post ADD COLUMN subtitle TEXT;"]);
+    }
+
+    #[test]
+    fn test_modifier_statements() {
+        let database = Database::new(DatabaseType::PostgreSQL, DatabaseDriver::Sqlx);
+        let columns = HashMap::from([("title".to_string(), "title TEXT NOT NULL".to_string())]);
+        let changes = vec![Change::from_clap("add:slug:text?!unique#index").unwrap()];
+        let (up, down) = get_statements("post", &changes, &columns, &database).unwrap();
+        assert_eq!(
+            up,
+            vec![
+                "ALTER TABLE post ADD COLUMN slug TEXT UNIQUE;",
+                "CREATE INDEX post_slug_idx ON post (slug);",
+            ]
+        );
+        assert_eq!(
+            down,
+            vec![
+                "DROP INDEX post_slug_idx;",
+                "ALTER TABLE post DROP COLUMN slug;",
+            ]
+        );
+
+        for change in [
+            "change:title:text!unique",
+            "change:title:text#index",
+            "change:title:text='draft'",
+            "change:title:int>=0",
+        ] {
+            let changes = vec![Change::from_clap(change).unwrap()];
+            let err = get_statements("post", &changes, &columns, &database).unwrap_err();
+            assert!(err.to_string().contains("constraints of column title"));
+        }
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let mut attributes = Attributes::from_clap("title:text!unique,body:text?,views:int")
+            .unwrap()
+            .0;
+        let changes = [
+            "remove:views",
+            "rename:body:content",
+            "change:title:varChar(200)",
+            "add:tags:text[]",
+        ]
+        .iter()
+        .map(|change| Change::from_clap(change).unwrap())
+        .collect::<Vec<_>>();
+        apply_changes(&mut attributes, &changes);
+
+        let attributes = attributes
+            .iter()
+            .map(Attribute::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attributes,
+            vec!["title:varChar(200)!unique", "content:text?", "tags:text[]"]
+        );
+    }
+}
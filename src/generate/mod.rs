@@ -1,24 +1,42 @@
 mod attribute;
+mod batch;
+mod conflict;
 mod crud;
 mod data_types;
+mod dependencies;
 mod exporters;
+mod migration;
 mod options;
+mod reference;
+mod resource;
 mod template;
 mod transformers;
 
-use crate::config::Config;
+use crate::add::add_dependencies;
+use crate::config::{Config, Database, DatabaseDriver, DatabaseType};
 
-use self::attribute::Attribute;
+use self::attribute::{Attribute, Attributes};
+use self::batch::generate_from_file;
+use self::conflict::OnConflict;
 use self::crud::CrudOperations;
-use self::data_types::IDType;
+use self::data_types::{EnumType, IDType};
+use self::dependencies::get_dependencies;
+use self::migration::{generate_migration, MigrationArgs};
 use self::options::GenerateOptions;
-use self::template::{get_api_template, get_db_template, get_model_template};
-use self::transformers::{DataTypeTransformer, PostgresMigration, RustStruct};
+use self::reference::ForeignKey;
+pub use self::resource::Resource;
+use self::template::{get_api_template, get_db_template, get_model_template, get_schema_template};
+use self::transformers::{
+    DataTypeTransformer, DieselSchema, DieselStruct, MysqlMigration, PostgresMigration,
+    SeaOrmMigration, SeaOrmStruct, SqliteMigration, SqlxStruct,
+};
 use anyhow::Result;
-use clap::Parser;
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
 use console::Term;
 use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Input};
+use std::path::{Path, PathBuf};
 
 trait FromClap: Sized {
     fn from_clap(str: &str) -> Result<Self>;
@@ -30,6 +48,9 @@ pub trait FromTerm<T>: Sized {
 
 #[derive(Parser, Debug)]
 pub struct GenerateArgs {
+    #[command(subcommand)]
+    pub command: Option<GenerateCommands>,
+
     #[arg(short, long)]
     /// Name of the generated files/data
     pub name: Option<String>,
@@ -42,12 +63,18 @@ pub struct GenerateArgs {
     /// Options on what to generate
     pub options: Option<Vec<GenerateOptions>>,
 
-    #[arg(short, long, value_delimiter = ',', value_parser = Attribute::from_clap, verbatim_doc_comment)]
+    #[arg(short, long, value_parser = Attributes::from_clap, verbatim_doc_comment)]
     /// Attributes that the generated files should posses.
     /// These are constructed as {name}:{type}.
     /// Fox example: title:text.
     /// You can add an question mark at the end if the attribute is optional.
-    pub attributes: Option<Vec<Attribute>>,
+    /// References to other tables are written as ref(table) or belongs_to(model),
+    /// optionally with the on delete behavior: author:belongs_to(user:set_null)?
+    /// Modifiers follow the type: !unique, #index, =default and checks like >=0.
+    /// For example: email:varChar(255)!unique#index or status:text=draft.
+    /// Enums list their values: status:enum(draft,published)=draft
+    /// Arrays follow the element type with brackets: tags:text[]
+    pub attributes: Option<Attributes>,
 
     #[arg(short = 'p', long, value_parser = CrudOperations::from_clap, verbatim_doc_comment)]
     /// Operations that should be generated for the api.
@@ -56,19 +83,39 @@ pub struct GenerateArgs {
     /// all, create, read, update, delete
     /// Or short notation without comma: crud
     pub operations: Option<CrudOperations>,
+
+    #[arg(long, conflicts_with_all = ["name", "id", "options", "attributes", "operations"])]
+    /// File with the resources to generate, in the format of the resources in schmiede.toml.
+    /// Written in toml, or yaml with a .yaml or .yml extension.
+    /// Referenced resources are generated first.
+    pub from: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = OnConflict::Fail)]
+    /// What happens to generated files that already exist
+    pub on_conflict: OnConflict,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GenerateCommands {
+    /// Generates a migration that alters an existing table and updates its models
+    Migration(MigrationArgs),
 }
 
 pub fn generate_files(args: GenerateArgs, term: Term, theme: ColorfulTheme) -> Result<()> {
-    let config = Config::from_file()?;
+    let mut config = Config::from_file()?;
+
+    if let Some(GenerateCommands::Migration(args)) = args.command {
+        return generate_migration(args, &mut config);
+    }
+    if let Some(path) = args.from {
+        return generate_from_file(&path, args.on_conflict, &mut config);
+    }
 
     let selected_options = match args.options {
         Some(options) => options,
         None => GenerateOptions::from_term(&term, &theme)?,
     };
-
-    if selected_options.iter().any(|o| *o == GenerateOptions::Sql) && config.database.is_none() {
-        anyhow::bail!("No database configuration found in config file. Please add a database configuration to the config file or select another option.");
-    }
+    check_options(&config, &selected_options)?;
 
     let operations: Option<CrudOperations> = match args.operations {
         Some(operations) => Some(operations),
@@ -99,9 +146,10 @@ pub fn generate_files(args: GenerateArgs, term: Term, theme: ColorfulTheme) -> R
             }
         }
     };
+    check_id(&config, &id)?;
 
     let attributes: Option<Vec<Attribute>> = match args.attributes {
-        Some(attributes) => Some(attributes),
+        Some(attributes) => Some(attributes.0),
         None => {
             if selected_options.iter().any(|x| x.requires_attributes()) {
                 Some(Attribute::from_term(&term, &theme)?)
@@ -111,29 +159,133 @@ pub fn generate_files(args: GenerateArgs, term: Term, theme: ColorfulTheme) -> R
         }
     };
 
-    for export_option in selected_options {
+    let resource = ResourceArgs {
+        name,
+        id,
+        attributes,
+        operations,
+        options: selected_options,
+    };
+    generate_resource(resource, Utc::now(), args.on_conflict, &mut config)
+}
+
+/// Everything that is known about a resource before its files are generated
+struct ResourceArgs {
+    name: String,
+    id: Option<IDType>,
+    attributes: Option<Vec<Attribute>>,
+    operations: Option<CrudOperations>,
+    options: Vec<GenerateOptions>,
+}
+
+fn check_options(config: &Config, options: &[GenerateOptions]) -> Result<()> {
+    if options.contains(&GenerateOptions::Sql) && config.database.is_none() {
+        anyhow::bail!("No database configuration found in config file. Please add a database configuration to the config file or select another option.");
+    }
+    Ok(())
+}
+
+fn check_id(config: &Config, id: &Option<IDType>) -> Result<()> {
+    let uses_sea_orm = config
+        .database
+        .as_ref()
+        .is_some_and(|database| database.database_driver == DatabaseDriver::SeaOrm);
+    if uses_sea_orm && matches!(id, Some(IDType::None)) {
+        anyhow::bail!("SeaORM entities require a primary id. Please select uuid or int as id.");
+    }
+    Ok(())
+}
+
+/// Generates the selected files of a resource and records it in schmiede.toml.
+/// Its migrations are created with the given timestamp.
+fn generate_resource(
+    resource: ResourceArgs,
+    timestamp: DateTime<Utc>,
+    on_conflict: OnConflict,
+    config: &mut Config,
+) -> Result<()> {
+    let ResourceArgs {
+        name,
+        id,
+        attributes,
+        operations,
+        options: selected_options,
+    } = resource;
+
+    let attributes = attributes
+        .map(|attributes| {
+            let id = id.clone().unwrap_or(IDType::Int);
+            attributes
+                .into_iter()
+                .map(|attribute| attribute.resolve(&name.to_lowercase(), &id, config))
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+
+    let uses_arrays = attributes
+        .iter()
+        .flatten()
+        .any(|attribute| attribute.data_type.is_array());
+    let supports_arrays = config
+        .database
+        .as_ref()
+        .is_none_or(|database| database.database_type == DatabaseType::PostgreSQL);
+    if uses_arrays && !supports_arrays {
+        anyhow::bail!("Array columns are only supported by PostgreSQL.");
+    }
+    if let Some(database) = &config.database {
+        check_diesel_support(attributes.iter().flatten(), database)?;
+    }
+
+    let generates_rust = selected_options
+        .iter()
+        .any(|option| matches!(option, GenerateOptions::Struct | GenerateOptions::Routes));
+
+    for export_option in selected_options.clone() {
         match export_option {
             GenerateOptions::Sql => {
+                let database = config.database.clone().unwrap();
                 let templates = get_db_template(
                     &name,
-                    get_rows(attributes.as_ref().unwrap(), export_option),
+                    get_rows(attributes.as_ref().unwrap(), export_option, &database),
+                    get_fields(attributes.as_ref().unwrap()),
+                    get_foreign_keys(attributes.as_ref().unwrap()),
+                    get_indexes(attributes.as_ref().unwrap()),
+                    get_enums(attributes.as_ref().unwrap()),
                     id.clone().expect("Should be present if SQL selected"),
-                    &config.database.clone().unwrap().database_driver,
+                    timestamp,
+                    &database,
                 );
                 for template in templates {
-                    template.export()?;
+                    template.export(on_conflict)?;
+                }
+
+                let schema_template = get_schema_template(
+                    &name,
+                    get_schema_columns(attributes.as_ref().unwrap(), &database.database_type),
+                    get_foreign_keys(attributes.as_ref().unwrap()),
+                    get_enums(attributes.as_ref().unwrap()),
+                    id.clone().expect("Should be present if SQL selected"),
+                    &database,
+                );
+                if let Some(schema_template) = schema_template {
+                    schema_template.export(on_conflict)?;
                 }
             }
             GenerateOptions::Struct => {
                 let struct_name = &name.to_case(Case::Pascal).clone();
+                let database = config.database.clone().unwrap();
                 let model_template = get_model_template(
                     &name,
                     struct_name,
                     id.clone().expect("Should be present if Struct selected"),
-                    get_rows(attributes.as_ref().unwrap(), export_option),
-                    config.database.clone().unwrap().database_driver,
+                    get_rows(attributes.as_ref().unwrap(), export_option, &database),
+                    get_fields(attributes.as_ref().unwrap()),
+                    get_foreign_keys(attributes.as_ref().unwrap()),
+                    get_enums(attributes.as_ref().unwrap()),
+                    database,
                 );
-                model_template.export()?;
+                model_template.export(on_conflict)?;
             }
             GenerateOptions::Routes => {
                 let struct_name = &name.to_case(Case::Pascal).clone();
@@ -143,9 +295,12 @@ pub fn generate_files(args: GenerateArgs, term: Term, theme: ColorfulTheme) -> R
                     operations
                         .clone()
                         .expect("Should be present if Routes selected"),
-                    config.database.clone().unwrap().database_driver,
+                    id.clone().expect("Should be present if Routes selected"),
+                    get_fields(attributes.as_ref().unwrap()),
+                    config.api_framework.clone(),
+                    &config.database.clone().unwrap(),
                 );
-                api_template.export()?;
+                api_template.export(on_conflict)?;
             } /* Disable for now until base is implemented
               GenerateOptions::Admin => {
                    let page_template = PageTemplate {
@@ -160,24 +315,148 @@ pub fn generate_files(args: GenerateArgs, term: Term, theme: ColorfulTheme) -> R
         };
     }
 
+    if let (true, Some(database), Some(attributes)) =
+        (generates_rust, &config.database, &attributes)
+    {
+        let rows = get_rows(attributes, GenerateOptions::Struct, database);
+        let dependencies = get_dependencies(
+            &rows,
+            id.as_ref().unwrap_or(&IDType::None),
+            &get_enums(attributes),
+            uses_arrays,
+            database,
+        );
+        add_dependencies(Path::new("."), dependencies)?;
+    }
+
+    if let Some(id) = id {
+        let mut resource = config
+            .resource(&name)
+            .cloned()
+            .unwrap_or_else(|| Resource::new(&name, id.clone()));
+        resource.id = id;
+        if let Some(attributes) = attributes {
+            resource.attributes = attributes;
+        }
+        if operations.is_some() {
+            resource.operations = operations;
+        }
+        resource.add_options(&selected_options);
+        resource.find_migrations(Path::new("."));
+        config.register_resource(resource);
+        config.update_config()?;
+    }
+
     Ok(())
 }
 
-fn get_rows(attributes: &[Attribute], option: GenerateOptions) -> Vec<String> {
-    let transformer: Box<dyn DataTypeTransformer> = match option {
-        GenerateOptions::Sql => Box::new(PostgresMigration {}),
-        GenerateOptions::Struct => Box::new(RustStruct {}),
+fn get_rows(attributes: &[Attribute], option: GenerateOptions, database: &Database) -> Vec<String> {
+    let transformer: Box<dyn DataTypeTransformer> = match (option, &database.database_type) {
+        (GenerateOptions::Sql, _) if database.database_driver == DatabaseDriver::SeaOrm => {
+            Box::new(SeaOrmMigration {})
+        }
+        (GenerateOptions::Sql, DatabaseType::PostgreSQL) => Box::new(PostgresMigration {}),
+        (GenerateOptions::Sql, DatabaseType::MySQL) => Box::new(MysqlMigration {}),
+        (GenerateOptions::Sql, DatabaseType::Sqlite) => Box::new(SqliteMigration {}),
+        (GenerateOptions::Struct, database_type) => match database.database_driver {
+            DatabaseDriver::Sqlx => Box::new(SqlxStruct {
+                database_type: database_type.clone(),
+                decimal: database.decimal.clone(),
+            }),
+            DatabaseDriver::Diesel => Box::new(DieselStruct {
+                database_type: database_type.clone(),
+                decimal: database.decimal.clone(),
+            }),
+            DatabaseDriver::SeaOrm => Box::new(SeaOrmStruct {
+                decimal: database.decimal.clone(),
+            }),
+        },
         _ => return vec![],
     };
 
     attributes
         .iter()
         .map(|attribute| {
-            if attribute.optional {
+            let row = if attribute.optional {
                 transformer.get_optional_row(&attribute.data_type, &attribute.name)
             } else {
                 transformer.get_row(&attribute.data_type, &attribute.name)
-            }
+            };
+            row + &transformer.get_modifiers(
+                &attribute.data_type,
+                &attribute.modifiers,
+                &attribute.name,
+            )
+        })
+        .collect()
+}
+
+/// Rejects columns that diesel can't read on the database
+fn check_diesel_support<'a>(
+    attributes: impl IntoIterator<Item = &'a Attribute>,
+    database: &Database,
+) -> Result<()> {
+    if database.database_driver != DatabaseDriver::Diesel {
+        return Ok(());
+    }
+    let transformer = DieselSchema {
+        database_type: database.database_type.clone(),
+    };
+    if let Some(attribute) = attributes
+        .into_iter()
+        .find(|attribute| !transformer.supports(&attribute.data_type))
+    {
+        anyhow::bail!(
+            "Column {} of type {} is not supported by diesel on {:?}. Please use time or timestampTZ instead.",
+            attribute.name,
+            attribute.data_type,
+            database.database_type
+        );
+    }
+    Ok(())
+}
+
+/// Columns of the `diesel::table!` in schema.rs
+fn get_schema_columns(attributes: &[Attribute], database_type: &DatabaseType) -> Vec<String> {
+    let transformer = DieselSchema {
+        database_type: database_type.clone(),
+    };
+    attributes
+        .iter()
+        .map(|attribute| match attribute.optional {
+            true => transformer.get_optional_row(&attribute.data_type, &attribute.name),
+            false => transformer.get_row(&attribute.data_type, &attribute.name),
         })
         .collect()
 }
+
+fn get_fields(attributes: &[Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .map(|attribute| attribute.name.to_case(Case::Snake))
+        .collect()
+}
+
+fn get_foreign_keys(attributes: &[Attribute]) -> Vec<ForeignKey> {
+    attributes
+        .iter()
+        .filter_map(Attribute::foreign_key)
+        .collect()
+}
+
+/// Columns with an index modifier, foreign keys get their index with the constraint
+fn get_indexes(attributes: &[Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.modifiers.index && attribute.reference.is_none())
+        .map(|attribute| attribute.name.to_case(Case::Snake))
+        .collect()
+}
+
+fn get_enums(attributes: &[Attribute]) -> Vec<EnumType> {
+    attributes
+        .iter()
+        .filter_map(Attribute::enum_type)
+        .cloned()
+        .collect()
+}
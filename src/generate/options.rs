@@ -3,8 +3,10 @@ use anyhow::{Context, Result};
 use clap::ValueEnum;
 use console::Term;
 use dialoguer::{theme::ColorfulTheme, MultiSelect};
+use serde::{Deserialize, Serialize};
 
-#[derive(ValueEnum, Clone, Debug, PartialEq)]
+#[derive(ValueEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum GenerateOptions {
     Sql,
     Struct,
@@ -16,11 +18,17 @@ impl GenerateOptions {
     const VALUES: [&'static str; 3] = ["sql", "struct", "routes"];
 
     pub fn requires_id(&self) -> bool {
-        matches!(self, GenerateOptions::Struct | GenerateOptions::Sql)
+        matches!(
+            self,
+            GenerateOptions::Struct | GenerateOptions::Sql | GenerateOptions::Routes
+        )
     }
 
     pub fn requires_attributes(&self) -> bool {
-        matches!(self, GenerateOptions::Struct | GenerateOptions::Sql)
+        matches!(
+            self,
+            GenerateOptions::Struct | GenerateOptions::Sql | GenerateOptions::Routes
+        )
     }
 
     pub fn requires_operations(&self) -> bool {
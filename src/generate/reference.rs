@@ -0,0 +1,364 @@
+use super::data_types::{DataType, IDType};
+use super::FromTerm;
+use crate::config::Config;
+use crate::files::read_to_string;
+use anyhow::{Context, Result};
+use console::Term;
+use convert_case::{Case, Casing};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use std::{fmt::Display, str::FromStr};
+
+const MODELS_PATH: &str = "src/common/models.rs";
+const SCHEMA_PATH: &str = "src/schema.rs";
+
+/// Foreign key from an attribute to the id of another table
+#[derive(Debug, Clone)]
+pub struct Reference {
+    /// Name of the referenced resource, as written
+    pub name: String,
+    pub kind: ReferenceKind,
+    pub on_delete: OnDelete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferenceKind {
+    /// Only the constraint, written as `ref(table)`
+    Ref,
+    /// Constraint and association in the model, written as `belongs_to(model)`
+    BelongsTo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnDelete {
+    Cascade,
+    SetNull,
+    Restrict,
+    NoAction,
+}
+
+impl Reference {
+    /// Parses `ref(table)` or `belongs_to(model)` with an optional `:on_delete` inside the brackets
+    pub fn parse(definition: &str, optional: bool) -> Result<Option<Self>> {
+        let Some((kind, rest)) = definition.split_once('(') else {
+            return Ok(None);
+        };
+        let kind = match kind {
+            "ref" => ReferenceKind::Ref,
+            "belongs_to" | "belongsTo" => ReferenceKind::BelongsTo,
+            _ => return Ok(None),
+        };
+        let target = rest
+            .strip_suffix(')')
+            .context("Missing closing bracket in reference")?;
+
+        let (table, on_delete) = match target.split_once(':') {
+            Some((table, on_delete)) => (table, on_delete.parse()?),
+            None => (target, OnDelete::default_for(optional)),
+        };
+        if table.is_empty() {
+            anyhow::bail!("Missing table in reference");
+        }
+
+        Ok(Some(Reference {
+            name: table.to_string(),
+            kind,
+            on_delete,
+        }))
+    }
+
+    /// Name of the referenced table, the lowercase name like the one of generated tables
+    pub fn table(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    pub fn model(&self) -> String {
+        self.name.to_case(Case::Pascal)
+    }
+
+    /// Column type matching the id of the referenced table.
+    /// The id is looked up in the resources of the config, the generated models and the
+    /// diesel schema, a table that references itself uses the id of the generated table.
+    pub fn data_type(&self, table: &str, id: &IDType, config: &Config) -> Result<DataType> {
+        let id = match self.table() == table.to_lowercase() {
+            true => id.clone(),
+            false => self.find_id_type(config).context(format!(
+                "The referenced table {} was not found. Please generate it first.",
+                self.table()
+            ))?,
+        };
+        Ok(match id {
+            IDType::Uuid => DataType::Uuid,
+            IDType::Int | IDType::None => DataType::Integer,
+        })
+    }
+
+    fn find_id_type(&self, config: &Config) -> Option<IDType> {
+        config
+            .resource(&self.name)
+            .map(|resource| resource.id.clone())
+            .or_else(|| {
+                let models = read_to_string(MODELS_PATH).ok()?;
+                find_id_type(&models, &self.name)
+            })
+            .or_else(|| {
+                let schema = read_to_string(SCHEMA_PATH).ok()?;
+                find_schema_id_type(&schema, &self.table())
+            })
+    }
+}
+
+/// Searches the struct or sea-orm module of a table for its id field
+fn find_id_type(models: &str, table: &str) -> Option<IDType> {
+    let definitions = [
+        format!("pub struct {} {{", table.to_case(Case::Pascal)),
+        format!("pub mod {} {{", table.to_lowercase()),
+    ];
+    let mut lines = models
+        .lines()
+        .skip_while(|line| !definitions.iter().any(|definition| line == definition));
+    lines.next()?;
+
+    lines
+        .take_while(|line| *line != "}")
+        .map(|line| line.trim().trim_start_matches("pub "))
+        .filter_map(|line| line.strip_prefix("id: "))
+        .find_map(|id| match id.trim_end_matches(',') {
+            "uuid::Uuid" | "Uuid" => Some(IDType::Uuid),
+            "i32" => Some(IDType::Int),
+            _ => None,
+        })
+}
+
+/// Searches the `diesel::table!` of a table for the type of its id column
+fn find_schema_id_type(schema: &str, table: &str) -> Option<IDType> {
+    let definition = format!("{} (id) {{", table);
+    let mut lines = schema
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != definition);
+    lines.next()?;
+
+    lines
+        .take_while(|line| *line != "}")
+        .filter_map(|line| line.strip_prefix("id -> "))
+        .find_map(|id| match id.trim_end_matches(',') {
+            "Uuid" => Some(IDType::Uuid),
+            "Int4" | "Integer" => Some(IDType::Int),
+            _ => None,
+        })
+}
+
+impl FromTerm<Self> for Reference {
+    fn from_term(term: &Term, theme: &ColorfulTheme) -> Result<Self> {
+        let table: String = Input::with_theme(theme)
+            .with_prompt("Which table does the field reference?")
+            .interact_on(term)
+            .context("Failed to get referenced table")?;
+
+        let kind = match Confirm::with_theme(theme)
+            .with_prompt("Should the model get an association to it (belongs_to)?")
+            .interact_on(term)
+            .context("Failed to get reference kind")?
+        {
+            true => ReferenceKind::BelongsTo,
+            false => ReferenceKind::Ref,
+        };
+
+        let on_delete = Select::with_theme(theme)
+            .with_prompt("What should happen when the referenced entry is deleted?")
+            .items(&OnDelete::VALUES)
+            .interact_on(term)
+            .context("Failed to get on delete behavior")?
+            .try_into()?;
+
+        Ok(Reference {
+            name: table,
+            kind,
+            on_delete,
+        })
+    }
+}
+
+impl Display for Reference {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.kind {
+            ReferenceKind::Ref => write!(fmt, "ref({}:{})", self.name, self.on_delete.value()),
+            ReferenceKind::BelongsTo => {
+                write!(fmt, "belongs_to({}:{})", self.name, self.on_delete.value())
+            }
+        }
+    }
+}
+
+impl OnDelete {
+    const VALUES: [&'static str; 4] = ["cascade", "set_null", "restrict", "no_action"];
+
+    /// Entries of required references are removed with the referenced one, optional ones are kept
+    fn default_for(optional: bool) -> Self {
+        match optional {
+            true => OnDelete::SetNull,
+            false => OnDelete::Cascade,
+        }
+    }
+
+    fn value(&self) -> &'static str {
+        match self {
+            OnDelete::Cascade => Self::VALUES[0],
+            OnDelete::SetNull => Self::VALUES[1],
+            OnDelete::Restrict => Self::VALUES[2],
+            OnDelete::NoAction => Self::VALUES[3],
+        }
+    }
+
+    /// Name of the matching `sea_query::ForeignKeyAction`
+    pub fn sea_orm_action(&self) -> String {
+        self.value().to_case(Case::Pascal)
+    }
+}
+
+impl Display for OnDelete {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OnDelete::Cascade => write!(fmt, "CASCADE"),
+            OnDelete::SetNull => write!(fmt, "SET NULL"),
+            OnDelete::Restrict => write!(fmt, "RESTRICT"),
+            OnDelete::NoAction => write!(fmt, "NO ACTION"),
+        }
+    }
+}
+
+impl FromStr for OnDelete {
+    type Err = anyhow::Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str.to_case(Case::Snake).as_str() {
+            "cascade" => Ok(OnDelete::Cascade),
+            "set_null" => Ok(OnDelete::SetNull),
+            "restrict" => Ok(OnDelete::Restrict),
+            "no_action" => Ok(OnDelete::NoAction),
+            _ => anyhow::bail!("Invalid on delete behavior"),
+        }
+    }
+}
+
+impl TryFrom<usize> for OnDelete {
+    type Error = anyhow::Error;
+
+    fn try_from(value: usize) -> Result<Self> {
+        match value {
+            0 => Ok(OnDelete::Cascade),
+            1 => Ok(OnDelete::SetNull),
+            2 => Ok(OnDelete::Restrict),
+            3 => Ok(OnDelete::NoAction),
+            _ => anyhow::bail!("Failed to convert on delete behavior from usize"),
+        }
+    }
+}
+
+/// Foreign key of a generated table as used by the migration and model templates
+pub struct ForeignKey {
+    pub column: String,
+    pub table: String,
+    pub model: String,
+    pub on_delete: OnDelete,
+    pub association: bool,
+}
+
+impl ForeignKey {
+    pub fn new(column: &str, reference: &Reference) -> Self {
+        Self {
+            column: column.to_case(Case::Snake),
+            table: reference.table(),
+            model: reference.model(),
+            on_delete: reference.on_delete.clone(),
+            association: reference.kind == ReferenceKind::BelongsTo,
+        }
+    }
+
+    /// Name of the column in the `Columns` iden of sea-orm migrations
+    pub fn column_iden(&self) -> String {
+        self.column.to_case(Case::Pascal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference() {
+        let reference = Reference::parse("ref(users)", false).unwrap().unwrap();
+        assert_eq!(reference.table(), "users");
+        assert_eq!(reference.kind, ReferenceKind::Ref);
+        assert_eq!(reference.on_delete, OnDelete::Cascade);
+
+        let reference = Reference::parse("belongs_to(user:restrict)", true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(reference.model(), "User");
+        assert_eq!(reference.kind, ReferenceKind::BelongsTo);
+        assert_eq!(reference.on_delete, OnDelete::Restrict);
+
+        let reference = Reference::parse("ref(users)", true).unwrap().unwrap();
+        assert_eq!(reference.on_delete, OnDelete::SetNull);
+
+        assert!(Reference::parse("text", false).unwrap().is_none());
+        assert!(Reference::parse("ref(users", false).is_err());
+        assert!(Reference::parse("ref(users:invalid)", false).is_err());
+    }
+
+    #[test]
+    fn test_reference_data_type() {
+        let mut config = Config::default();
+        config.register_resource(crate::generate::Resource::new("user", IDType::Uuid));
+
+        let reference = Reference::parse("ref(user)", false).unwrap().unwrap();
+        let data_type = reference.data_type("post", &IDType::Int, &config).unwrap();
+        assert!(matches!(data_type, DataType::Uuid));
+
+        let reference = Reference::parse("ref(post)", true).unwrap().unwrap();
+        let data_type = reference.data_type("post", &IDType::Int, &config).unwrap();
+        assert!(matches!(data_type, DataType::Integer));
+
+        let reference = Reference::parse("ref(author)", false).unwrap().unwrap();
+        let error = reference
+            .data_type("post", &IDType::Int, &config)
+            .unwrap_err();
+        assert!(error.to_string().contains("author"));
+    }
+
+    #[test]
+    fn test_find_id_type() {
+        let models = r#"
+#[derive(FromRow, Serialize, Clone, Debug)]
+pub struct User {
+    id: uuid::Uuid,
+    name: String,
+}
+
+pub mod comment {
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+    }
+}
+"#;
+        assert!(matches!(find_id_type(models, "user"), Some(IDType::Uuid)));
+        assert!(matches!(find_id_type(models, "comment"), Some(IDType::Int)));
+        assert!(find_id_type(models, "post").is_none());
+
+        let schema = r#"
+diesel::table! {
+    user (id) {
+        id -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+"#;
+        assert!(matches!(
+            find_schema_id_type(schema, "user"),
+            Some(IDType::Uuid)
+        ));
+        assert!(find_schema_id_type(schema, "post").is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use super::attribute::Attribute;
+use super::crud::CrudOperations;
+use super::data_types::IDType;
+use super::options::GenerateOptions;
+use super::FromClap;
+use crate::files::read_dir;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::Path;
+
+/// Resource generated by schmiede, recorded in schmiede.toml
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Resource {
+    pub name: String,
+    pub id: IDType,
+    /// Attributes in the syntax of `generate --attributes`, for example `email:varChar(255)!unique`
+    #[serde(default)]
+    pub attributes: Vec<Attribute>,
+    #[serde(default)]
+    pub options: Vec<GenerateOptions>,
+    /// Operations of the generated routes, for example `all` or `create,read`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operations: Option<CrudOperations>,
+    /// Migration files of the table, in the order they run
+    #[serde(default)]
+    pub migrations: Vec<String>,
+}
+
+impl Resource {
+    pub fn new(name: &str, id: IDType) -> Self {
+        Self {
+            name: name.to_string(),
+            id,
+            attributes: vec![],
+            options: vec![],
+            operations: None,
+            migrations: vec![],
+        }
+    }
+
+    /// Name of the table, as used in the migrations
+    pub fn table(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    /// Whether the resource belongs to the table, which can be written in any case
+    pub fn is_table(&self, table: &str) -> bool {
+        self.table() == table.to_lowercase()
+    }
+
+    /// Records the options that were generated, keeping the ones from earlier runs
+    pub fn add_options(&mut self, options: &[GenerateOptions]) {
+        for option in options {
+            if !self.options.contains(option) {
+                self.options.push(option.clone());
+            }
+        }
+    }
+
+    /// Collects the migrations of the table from the sqlx/diesel and sea-orm migration directories
+    pub fn find_migrations(&mut self, project_path: &Path) {
+        let table = self.table();
+        let names = [table.clone(), format!("alter_{}", table)];
+        let sea_orm_suffix = format!("_create_{}_table.rs", table);
+
+        let mut migrations = vec![];
+        for (dir, prefix) in [
+            ("migrations", "migrations/"),
+            ("migration/src", "migration/src/"),
+        ] {
+            let Ok(entries) = read_dir(project_path.join(dir)) else {
+                continue;
+            };
+            for entry in entries {
+                let file_name = entry
+                    .file_name()
+                    .map_or(String::new(), |name| name.to_string_lossy().to_string());
+                // Migrations are prefixed by their timestamp
+                let migration = file_name
+                    .split_once('_')
+                    .map_or("", |(_, name)| name)
+                    .trim_end_matches(".up.sql")
+                    .trim_end_matches(".down.sql");
+                if names.iter().any(|name| name == migration)
+                    || file_name.ends_with(&sea_orm_suffix)
+                {
+                    migrations.push(format!("{}{}", prefix, file_name));
+                }
+            }
+        }
+        migrations.sort();
+        self.migrations = migrations;
+    }
+}
+
+impl Serialize for Attribute {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Attribute {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let attribute = String::deserialize(deserializer)?;
+        Attribute::from_clap(&attribute).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for CrudOperations {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CrudOperations {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let operations = String::deserialize(deserializer)?;
+        CrudOperations::from_clap(&operations).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+
+    #[test]
+    fn test_resource_from_toml() {
+        let resource: Resource = toml::from_str(
+            r#"
+name = "post"
+id = "uuid"
+attributes = ["title:varChar(100)!unique", "author:ref(user:cascade)?"]
+options = ["sql", "struct"]
+operations = "cr"
+"#,
+        )
+        .unwrap();
+
+        assert!(matches!(resource.id, IDType::Uuid));
+        assert_eq!(resource.attributes[0].name, "title");
+        assert!(resource.attributes[0].modifiers.unique);
+        assert!(resource.attributes[1].reference.is_some());
+        assert_eq!(
+            resource.options,
+            vec![GenerateOptions::Sql, GenerateOptions::Struct]
+        );
+
+        assert_eq!(
+            resource.operations.as_ref().unwrap().to_string(),
+            "create,read"
+        );
+
+        let toml = toml::to_string(&resource).unwrap();
+        assert!(toml.contains(
+            r#"attributes = ["title:varChar(100)!unique", "author:ref(user:cascade)?"]"#
+        ));
+    }
+
+    #[test]
+    fn test_find_migrations() {
+        let project_path = tempfile::tempdir().unwrap();
+        let migrations = project_path.path().join("migrations");
+        create_dir_all(migrations.join("2024-01-01-000000_post")).unwrap();
+        create_dir_all(migrations.join("2024-01-02-000000_alter_post")).unwrap();
+        create_dir_all(migrations.join("2024-01-01-000000_post_tag")).unwrap();
+        write(migrations.join("20240103000000_post.up.sql"), "").unwrap();
+
+        let mut resource = Resource::new("Post", IDType::Int);
+        resource.find_migrations(project_path.path());
+        assert_eq!(
+            resource.migrations,
+            vec![
+                "migrations/2024-01-01-000000_post",
+                "migrations/2024-01-02-000000_alter_post",
+                "migrations/20240103000000_post.up.sql",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_camel_case_table() {
+        let resource = Resource::new("blogPost", IDType::Int);
+        assert_eq!(resource.table(), "blogpost");
+        assert!(resource.is_table("blogPost"));
+        assert!(resource.is_table("BlogPost"));
+        assert!(resource.is_table("blogpost"));
+        assert!(!resource.is_table("blog_post"));
+
+        let reference = super::super::reference::Reference::parse("belongs_to(blogPost)", false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(reference.table(), resource.table());
+        assert_eq!(reference.model(), "BlogPost");
+    }
+}
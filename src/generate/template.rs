@@ -1,58 +1,227 @@
 use askama::Template;
+use chrono::{DateTime, Utc};
+use convert_case::{Case, Casing};
 
-use crate::config::DatabaseDriver;
+use crate::config::{ApiFramework, Database, DatabaseDriver, DatabaseType, UuidExtension};
 
-use super::crud::CrudOperations;
-use super::data_types::IDType;
+use super::crud::{CrudOperations, SpecificOperation};
+use super::data_types::{DataType, EnumType, IDType};
 use super::exporters::Export;
+use super::reference::ForeignKey;
+use super::transformers::{DataTypeTransformer, DieselSchema};
 
 #[derive(Template)]
-#[template(path = "generate/db/sqlx/up.sql.templ", escape = "html")]
+#[template(path = "generate/db/sqlx/up.sql.templ", escape = "none")]
 pub struct SqlxUpTemplate<'a> {
     pub name: &'a str,
+    pub timestamp: DateTime<Utc>,
     pub rows: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indexes: Vec<String>,
+    pub enums: Vec<EnumType>,
     pub id: IDType,
+    pub database_type: DatabaseType,
+    pub uuid_extension: UuidExtension,
 }
 
 #[derive(Template)]
-#[template(path = "generate/db/sqlx/down.sql.templ", escape = "html")]
+#[template(path = "generate/db/sqlx/down.sql.templ", escape = "none")]
 pub struct SqlxDownTemplate<'a> {
     pub name: &'a str,
+    pub timestamp: DateTime<Utc>,
+    pub indexes: Vec<String>,
+    pub enums: Vec<EnumType>,
+    pub database_type: DatabaseType,
 }
 
 #[derive(Template)]
-#[template(path = "generate/db/diesel/up.sql.templ", escape = "html")]
+#[template(path = "generate/db/diesel/up.sql.templ", escape = "none")]
 pub struct DieselUpTemplate<'a> {
     pub name: &'a str,
+    pub timestamp: DateTime<Utc>,
     pub rows: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indexes: Vec<String>,
+    pub enums: Vec<EnumType>,
     pub id: IDType,
+    pub database_type: DatabaseType,
+    pub uuid_extension: UuidExtension,
 }
 
 #[derive(Template)]
-#[template(path = "generate/db/diesel/down.sql.templ", escape = "html")]
+#[template(path = "generate/db/diesel/down.sql.templ", escape = "none")]
 pub struct DieselDownTemplate<'a> {
     pub name: &'a str,
+    pub timestamp: DateTime<Utc>,
+    pub indexes: Vec<String>,
+    pub enums: Vec<EnumType>,
+    pub database_type: DatabaseType,
 }
 
+#[derive(Template)]
+#[template(path = "generate/db/diesel/schema.rs.templ", escape = "none")]
+pub struct DieselSchemaTemplate<'a> {
+    pub name: &'a str,
+    pub columns: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub enums: Vec<EnumType>,
+    pub id: IDType,
+    pub database_type: DatabaseType,
+}
+
+impl DieselSchemaTemplate<'_> {
+    fn id_type(&self) -> String {
+        let transformer = DieselSchema {
+            database_type: self.database_type.clone(),
+        };
+        match self.id {
+            IDType::Uuid => transformer.get_type(&DataType::Uuid),
+            IDType::Int | IDType::None => transformer.get_type(&DataType::Integer),
+        }
+    }
+
+    fn timestamp_type(&self) -> &'static str {
+        match self.database_type {
+            DatabaseType::PostgreSQL => "Timestamptz",
+            DatabaseType::MySQL | DatabaseType::Sqlite => "Timestamp",
+        }
+    }
+
+    /// Declarations of the postgres enum types for `schema::sql_types`
+    pub fn sql_types(&self) -> Vec<String> {
+        if self.database_type != DatabaseType::PostgreSQL {
+            return vec![];
+        }
+        self.enums
+            .iter()
+            .map(|enum_type| {
+                format!(
+                    "    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]\n    #[diesel(postgres_type(name = \"{}\"))]\n    pub struct {};",
+                    enum_type.name,
+                    enum_type.rust_name()
+                )
+            })
+            .collect()
+    }
+}
+
+/// Schema of the table for diesel, tables without a primary key are left out like `diesel print-schema` does
+pub fn get_schema_template<'a>(
+    name: &'a str,
+    columns: Vec<String>,
+    foreign_keys: Vec<ForeignKey>,
+    enums: Vec<EnumType>,
+    id: IDType,
+    database: &Database,
+) -> Option<Box<dyn Export + 'a>> {
+    match (&database.database_driver, &id) {
+        (DatabaseDriver::Diesel, IDType::Uuid | IDType::Int) => {
+            Some(Box::new(DieselSchemaTemplate {
+                name,
+                columns,
+                foreign_keys,
+                enums,
+                id,
+                database_type: database.database_type.clone(),
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Template)]
+#[template(path = "generate/db/sea_orm/migration.rs.templ", escape = "none")]
+pub struct SeaOrmMigrationTemplate<'a> {
+    pub name: &'a str,
+    pub timestamp: DateTime<Utc>,
+    pub rows: Vec<String>,
+    pub columns: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indexes: Vec<String>,
+    pub enums: Vec<EnumType>,
+    pub id: IDType,
+}
+
+impl SeaOrmMigrationTemplate<'_> {
+    fn column_iden(&self, column: &str) -> String {
+        column.to_case(Case::Pascal)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn get_db_template<'a>(
     name: &'a str,
     rows: Vec<String>,
+    columns: Vec<String>,
+    foreign_keys: Vec<ForeignKey>,
+    indexes: Vec<String>,
+    enums: Vec<EnumType>,
     id: IDType,
-    database_driver: &DatabaseDriver,
+    timestamp: DateTime<Utc>,
+    database: &Database,
 ) -> Vec<Box<dyn Export + 'a>> {
-    match database_driver {
+    let database_type = database.database_type.clone();
+    match database.database_driver {
         DatabaseDriver::Sqlx => {
             vec![
-                Box::new(SqlxUpTemplate { name, rows, id }),
-                Box::new(SqlxDownTemplate { name }),
+                Box::new(SqlxUpTemplate {
+                    name,
+                    timestamp,
+                    rows,
+                    foreign_keys,
+                    indexes: indexes.clone(),
+                    enums: enums.clone(),
+                    id,
+                    database_type: database_type.clone(),
+                    uuid_extension: database.uuid_extension.clone(),
+                }),
+                Box::new(SqlxDownTemplate {
+                    name,
+                    timestamp,
+                    indexes,
+                    enums,
+                    database_type,
+                }),
             ]
         }
         DatabaseDriver::Diesel => {
             vec![
-                Box::new(DieselUpTemplate { name, rows, id }),
-                Box::new(DieselDownTemplate { name }),
+                Box::new(DieselUpTemplate {
+                    name,
+                    timestamp,
+                    rows,
+                    foreign_keys,
+                    indexes: indexes.clone(),
+                    enums: enums.clone(),
+                    id,
+                    database_type: database_type.clone(),
+                    uuid_extension: database.uuid_extension.clone(),
+                }),
+                Box::new(DieselDownTemplate {
+                    name,
+                    timestamp,
+                    indexes,
+                    enums,
+                    database_type,
+                }),
             ]
         }
+        DatabaseDriver::SeaOrm => {
+            // sea-orm-migration migrations are a single file with up and down
+            vec![Box::new(SeaOrmMigrationTemplate {
+                name,
+                timestamp,
+                rows,
+                columns: columns
+                    .iter()
+                    .map(|column| column.to_case(Case::Pascal))
+                    .collect(),
+                foreign_keys,
+                indexes,
+                enums,
+                id,
+            })]
+        }
     }
 }
 
@@ -60,9 +229,11 @@ pub fn get_db_template<'a>(
 #[template(path = "generate/models/sqlx.rs.templ", escape = "none")]
 pub struct SqlxModelTemplate<'a> {
     pub id: IDType,
+    #[allow(dead_code)]
     pub name: &'a str,
     pub struct_name: &'a str,
     pub rows: Vec<String>,
+    pub enums: Vec<EnumType>,
 }
 
 #[derive(Template)]
@@ -72,27 +243,77 @@ pub struct DieselModelTemplate<'a> {
     pub name: &'a str,
     pub struct_name: &'a str,
     pub rows: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub enums: Vec<EnumType>,
+    pub database_type: DatabaseType,
+}
+
+#[derive(Template)]
+#[template(path = "generate/models/sea_orm.rs.templ", escape = "none")]
+pub struct SeaOrmModelTemplate<'a> {
+    pub id: IDType,
+    pub name: &'a str,
+    pub struct_name: &'a str,
+    pub rows: Vec<String>,
+    pub fields: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub enums: Vec<EnumType>,
 }
 
+impl DieselModelTemplate<'_> {
+    fn associations(&self) -> Vec<&ForeignKey> {
+        self.foreign_keys
+            .iter()
+            .filter(|key| key.association)
+            .collect()
+    }
+}
+
+impl SeaOrmModelTemplate<'_> {
+    fn associations(&self) -> Vec<&ForeignKey> {
+        self.foreign_keys
+            .iter()
+            .filter(|key| key.association)
+            .collect()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn get_model_template<'a>(
     name: &'a str,
     struct_name: &'a str,
     id: IDType,
     rows: Vec<String>,
-    database_driver: DatabaseDriver,
+    fields: Vec<String>,
+    foreign_keys: Vec<ForeignKey>,
+    enums: Vec<EnumType>,
+    database: Database,
 ) -> Box<dyn Export + 'a> {
-    match database_driver {
+    match database.database_driver {
         DatabaseDriver::Sqlx => Box::new(SqlxModelTemplate {
             id,
             name,
             struct_name,
             rows,
+            enums,
         }),
         DatabaseDriver::Diesel => Box::new(DieselModelTemplate {
             id,
             name,
             struct_name,
             rows,
+            foreign_keys,
+            enums,
+            database_type: database.database_type,
+        }),
+        DatabaseDriver::SeaOrm => Box::new(SeaOrmModelTemplate {
+            id,
+            name,
+            struct_name,
+            rows,
+            fields,
+            foreign_keys,
+            enums,
         }),
     }
 }
@@ -112,7 +333,156 @@ pub struct PageTemplate<'a> {
 pub struct AxumDieselTemplate<'a> {
     pub name: &'a str,
     pub struct_name: &'a str,
-    pub crud_operations: CrudOperations,
+    pub id: IDType,
+    pub operations: RouteOperations,
+}
+
+/// Handlers to generate, the ones working on a single entry need an id
+pub struct RouteOperations {
+    pub create: bool,
+    pub read: bool,
+    pub read_one: bool,
+    pub update: bool,
+    pub delete: bool,
+}
+
+impl RouteOperations {
+    pub fn new(crud_operations: &CrudOperations, id: &IDType) -> Self {
+        let has_id = !matches!(id, IDType::None);
+        Self {
+            create: crud_operations.contains(&SpecificOperation::Create),
+            read: crud_operations.contains(&SpecificOperation::Read),
+            read_one: has_id && crud_operations.contains(&SpecificOperation::Read),
+            update: has_id && crud_operations.contains(&SpecificOperation::Update),
+            delete: has_id && crud_operations.contains(&SpecificOperation::Delete),
+        }
+    }
+
+    pub fn has_single(&self) -> bool {
+        self.read_one || self.update || self.delete
+    }
+
+    pub fn uses_json(&self) -> bool {
+        self.read || self.create || self.update
+    }
+
+    pub fn uses_status_code(&self) -> bool {
+        self.create || self.has_single()
+    }
+
+    /// Axum method routers that start a `.route`, the others are chained on them
+    pub fn axum_routing(&self, name: &str) -> String {
+        let mut methods = [self.collection_routes(name), self.single_routes(name)]
+            .iter()
+            .filter_map(|routes| routes.first().map(|(method, _)| *method))
+            .collect::<Vec<_>>();
+        methods.sort();
+        methods.dedup();
+        methods.join(", ")
+    }
+
+    /// (method, handler) pairs for the collection route
+    pub fn collection_routes(&self, name: &str) -> Vec<(&'static str, String)> {
+        let name = name.to_lowercase();
+        let mut routes = vec![];
+        if self.read {
+            routes.push(("get", format!("get_{}s", name)));
+        }
+        if self.create {
+            routes.push(("post", format!("new_{}", name)));
+        }
+        routes
+    }
+
+    /// (method, handler) pairs for the route of a single entry
+    pub fn single_routes(&self, name: &str) -> Vec<(&'static str, String)> {
+        let name = name.to_lowercase();
+        let mut routes = vec![];
+        if self.read_one {
+            routes.push(("get", format!("get_{}", name)));
+        }
+        if self.update {
+            routes.push(("patch", format!("update_{}", name)));
+        }
+        if self.delete {
+            routes.push(("delete", format!("delete_{}", name)));
+        }
+        routes
+    }
+}
+
+/// Queries for the sqlx handlers of a table
+pub struct SqlxQueries {
+    pub select_all: String,
+    pub select_one: String,
+    pub insert: String,
+    pub update: String,
+    pub delete: String,
+    /// MySQL has no RETURNING, so changed rows are selected again
+    pub returning: bool,
+    /// MySQL can't return a generated uuid, so it is created in rust
+    pub generate_id: bool,
+    /// MySQL returns the auto increment id of an insert
+    pub last_insert_id: bool,
+    pub fields: Vec<String>,
+}
+
+impl SqlxQueries {
+    pub fn new(name: &str, fields: Vec<String>, id: &IDType, database_type: &DatabaseType) -> Self {
+        let table = name.to_lowercase();
+        let placeholder = |i: usize| match database_type {
+            DatabaseType::PostgreSQL => format!("${}", i),
+            DatabaseType::MySQL | DatabaseType::Sqlite => "?".to_string(),
+        };
+        let returning = *database_type != DatabaseType::MySQL;
+        let generate_id = !returning && matches!(id, IDType::Uuid);
+        let last_insert_id = !returning && matches!(id, IDType::Int);
+        let suffix = if returning { " RETURNING *" } else { "" };
+
+        let mut insert_columns = fields.clone();
+        if generate_id {
+            insert_columns.insert(0, "id".to_string());
+        }
+        let insert_values = (1..=insert_columns.len())
+            .map(placeholder)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let update_set = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| format!("{} = {}", field, placeholder(i + 1)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Self {
+            select_all: format!("SELECT * FROM {}", table),
+            select_one: format!("SELECT * FROM {} WHERE id = {}", table, placeholder(1)),
+            insert: format!(
+                "INSERT INTO {} ({}) VALUES ({}){}",
+                table,
+                insert_columns.join(", "),
+                insert_values,
+                suffix
+            ),
+            update: format!(
+                "UPDATE {} SET {} WHERE id = {}{}",
+                table,
+                update_set,
+                placeholder(fields.len() + 1),
+                suffix
+            ),
+            delete: format!("DELETE FROM {} WHERE id = {}", table, placeholder(1)),
+            returning,
+            generate_id,
+            last_insert_id,
+            fields,
+        }
+    }
+
+    /// Whether the created row can be read back to return it
+    pub fn returns_created(&self) -> bool {
+        self.returning || self.generate_id || self.last_insert_id
+    }
 }
 
 #[derive(Template)]
@@ -120,25 +490,481 @@ pub struct AxumDieselTemplate<'a> {
 pub struct AxumSqlxTemplate<'a> {
     pub name: &'a str,
     pub struct_name: &'a str,
-    pub crud_operations: CrudOperations,
+    pub id: IDType,
+    pub operations: RouteOperations,
+    pub queries: SqlxQueries,
+}
+
+#[derive(Template)]
+#[template(path = "generate/api/actix_diesel.rs.templ", escape = "none")]
+pub struct ActixDieselTemplate<'a> {
+    pub name: &'a str,
+    pub struct_name: &'a str,
+    pub id: IDType,
+    pub operations: RouteOperations,
+}
+
+#[derive(Template)]
+#[template(path = "generate/api/actix_sqlx.rs.templ", escape = "none")]
+pub struct ActixSqlxTemplate<'a> {
+    pub name: &'a str,
+    pub struct_name: &'a str,
+    pub id: IDType,
+    pub operations: RouteOperations,
+    pub queries: SqlxQueries,
+}
+
+#[derive(Template)]
+#[template(path = "generate/api/axum_sea_orm.rs.templ", escape = "none")]
+pub struct AxumSeaOrmTemplate<'a> {
+    pub name: &'a str,
+    pub struct_name: &'a str,
+    pub id: IDType,
+    pub operations: RouteOperations,
+}
+
+#[derive(Template)]
+#[template(path = "generate/api/actix_sea_orm.rs.templ", escape = "none")]
+pub struct ActixSeaOrmTemplate<'a> {
+    pub name: &'a str,
+    pub struct_name: &'a str,
+    pub id: IDType,
+    pub operations: RouteOperations,
 }
 
 pub fn get_api_template<'a>(
     name: &'a str,
     struct_name: &'a str,
     crud_operations: CrudOperations,
-    database_driver: DatabaseDriver,
+    id: IDType,
+    fields: Vec<String>,
+    api_framework: ApiFramework,
+    database: &Database,
 ) -> Box<dyn Export + 'a> {
-    match database_driver {
-        DatabaseDriver::Sqlx => Box::new(AxumSqlxTemplate {
+    match (api_framework, &database.database_driver) {
+        (ApiFramework::Axum, DatabaseDriver::Sqlx) => Box::new(AxumSqlxTemplate {
             name,
             struct_name,
-            crud_operations,
+            operations: RouteOperations::new(&crud_operations, &id),
+            queries: SqlxQueries::new(name, fields, &id, &database.database_type),
+            id,
         }),
-        DatabaseDriver::Diesel => Box::new(AxumDieselTemplate {
+        (ApiFramework::Axum, DatabaseDriver::Diesel) => Box::new(AxumDieselTemplate {
             name,
             struct_name,
-            crud_operations,
+            operations: RouteOperations::new(&crud_operations, &id),
+            id,
         }),
+        (ApiFramework::Actix, DatabaseDriver::Sqlx) => Box::new(ActixSqlxTemplate {
+            name,
+            struct_name,
+            operations: RouteOperations::new(&crud_operations, &id),
+            queries: SqlxQueries::new(name, fields, &id, &database.database_type),
+            id,
+        }),
+        (ApiFramework::Actix, DatabaseDriver::Diesel) => Box::new(ActixDieselTemplate {
+            name,
+            struct_name,
+            operations: RouteOperations::new(&crud_operations, &id),
+            id,
+        }),
+        (ApiFramework::Axum, DatabaseDriver::SeaOrm) => Box::new(AxumSeaOrmTemplate {
+            name,
+            struct_name,
+            operations: RouteOperations::new(&crud_operations, &id),
+            id,
+        }),
+        (ApiFramework::Actix, DatabaseDriver::SeaOrm) => Box::new(ActixSeaOrmTemplate {
+            name,
+            struct_name,
+            operations: RouteOperations::new(&crud_operations, &id),
+            id,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reference::Reference;
+    use super::*;
+
+    #[test]
+    fn test_mysql_up_migration() {
+        let template = SqlxUpTemplate {
+            name: "Post",
+            timestamp: Utc::now(),
+            rows: vec!["title TEXT NOT NULL".to_string()],
+            foreign_keys: vec![],
+            indexes: vec![],
+            enums: vec![],
+            id: IDType::Int,
+            database_type: DatabaseType::MySQL,
+            uuid_extension: UuidExtension::default(),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("id INT NOT NULL AUTO_INCREMENT PRIMARY KEY,"));
+        assert!(rendered.contains("title TEXT NOT NULL,"));
+        assert!(rendered.contains("ON UPDATE CURRENT_TIMESTAMP"));
+        assert!(!rendered.contains("manage_updated_at"));
+    }
+
+    #[test]
+    fn test_sqlite_up_migration() {
+        let template = DieselUpTemplate {
+            name: "Post",
+            timestamp: Utc::now(),
+            rows: vec!["title TEXT NOT NULL".to_string()],
+            foreign_keys: vec![],
+            indexes: vec![],
+            enums: vec![],
+            id: IDType::Int,
+            database_type: DatabaseType::Sqlite,
+            uuid_extension: UuidExtension::default(),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("id INTEGER PRIMARY KEY AUTOINCREMENT,"));
+        assert!(rendered.contains("CREATE TRIGGER post_updated_at AFTER UPDATE ON post"));
+        assert!(!rendered.contains("diesel_manage_updated_at"));
+    }
+
+    #[test]
+    fn test_sea_orm_migration() {
+        let template = SeaOrmMigrationTemplate {
+            name: "blogPost",
+            timestamp: Utc::now(),
+            rows: vec!["ColumnDef::new(Columns::Title).text().not_null()".to_string()],
+            columns: vec!["Title".to_string()],
+            foreign_keys: vec![],
+            indexes: vec![],
+            enums: vec![],
+            id: IDType::Int,
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("#[sea_orm(iden = \"blogpost\")]"));
+        assert!(rendered.contains(".col(ColumnDef::new(Columns::Title).text().not_null())"));
+        assert!(rendered.contains("    Id,\n    Title,\n    CreatedAt,"));
+    }
+
+    #[test]
+    fn test_axum_sqlx_routes() {
+        let template = AxumSqlxTemplate {
+            name: "Post",
+            struct_name: "Post",
+            id: IDType::Int,
+            operations: RouteOperations::new(
+                &CrudOperations::Specific(vec![
+                    SpecificOperation::Create,
+                    SpecificOperation::Delete,
+                ]),
+                &IDType::Int,
+            ),
+            queries: SqlxQueries::new(
+                "Post",
+                vec!["title".to_string(), "body".to_string()],
+                &IDType::Int,
+                &DatabaseType::PostgreSQL,
+            ),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("INSERT INTO post (title, body) VALUES ($1, $2) RETURNING *"));
+        assert!(rendered.contains("Path(post_id): Path<i32>"));
+        assert!(rendered.contains(".route(\"/\", post(new_post))"));
+        assert!(rendered.contains(".route(\"/:post_id\", delete(delete_post))"));
+        assert!(rendered.contains("routing::{delete, post},"));
+        assert!(!rendered.contains("get_post"));
+        assert!(!rendered.contains("update_post"));
+        assert!(!rendered.contains("UpdatePost"));
+    }
+
+    #[test]
+    fn test_actix_sqlx_mysql_routes() {
+        let template = ActixSqlxTemplate {
+            name: "Post",
+            struct_name: "Post",
+            id: IDType::Uuid,
+            operations: RouteOperations::new(&CrudOperations::All, &IDType::Uuid),
+            queries: SqlxQueries::new(
+                "Post",
+                vec!["title".to_string()],
+                &IDType::Uuid,
+                &DatabaseType::MySQL,
+            ),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("let post_id = uuid::Uuid::new_v4();"));
+        assert!(rendered.contains("INSERT INTO post (id, title) VALUES (?, ?)\""));
+        assert!(rendered.contains("UPDATE post SET title = ? WHERE id = ?\""));
+        assert!(rendered.contains("path: web::Path<uuid::Uuid>"));
+        assert!(rendered.contains(".route(web::patch().to(update_post))"));
+        assert!(!rendered.contains("RETURNING"));
+    }
+
+    #[test]
+    fn test_actix_diesel_routes() {
+        let template = ActixDieselTemplate {
+            name: "Post",
+            struct_name: "Post",
+            id: IDType::Uuid,
+            operations: RouteOperations::new(&CrudOperations::All, &IDType::Uuid),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("path: web::Path<uuid::Uuid>"));
+        assert!(!rendered.contains("Path<i32>"));
+        assert!(rendered.contains("diesel::update(post.find(post_id))"));
+        assert!(rendered.contains(".route(web::post().to(new_post))"));
+        assert!(rendered.contains(".route(web::delete().to(delete_post))"));
+    }
+
+    #[test]
+    fn test_actix_diesel_crud_subset() {
+        let template = ActixDieselTemplate {
+            name: "Post",
+            struct_name: "Post",
+            id: IDType::Int,
+            operations: RouteOperations::new(
+                &CrudOperations::Specific(vec![SpecificOperation::Create, SpecificOperation::Read]),
+                &IDType::Int,
+            ),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("use actix_web::{web, HttpResponse};"));
+        assert!(rendered.contains("path: web::Path<i32>"));
+        assert!(rendered.contains(".route(web::get().to(get_posts))"));
+        assert!(rendered.contains(".route(web::post().to(new_post))"));
+        assert!(rendered.contains(".route(web::get().to(get_post))"));
+        assert!(!rendered.contains("update_post"));
+        assert!(!rendered.contains("delete_post"));
+        assert!(!rendered.contains("UpdatePost"));
+    }
+
+    #[test]
+    fn test_sea_orm_uuid_routes() {
+        let template = AxumSeaOrmTemplate {
+            name: "Author",
+            struct_name: "Author",
+            id: IDType::Uuid,
+            operations: RouteOperations::new(&CrudOperations::All, &IDType::Uuid),
+        };
+        let rendered = template.render().unwrap();
+        assert!(rendered.contains("Path(author_id): Path<uuid::Uuid>"));
+        assert!(!rendered.contains("Path<i32>"));
+
+        let template = ActixSeaOrmTemplate {
+            name: "Author",
+            struct_name: "Author",
+            id: IDType::Uuid,
+            operations: RouteOperations::new(&CrudOperations::All, &IDType::Uuid),
+        };
+        let rendered = template.render().unwrap();
+        assert!(rendered.contains("path: web::Path<uuid::Uuid>"));
+        assert!(!rendered.contains("Path<i32>"));
+    }
+
+    #[test]
+    fn test_read_only_routes() {
+        let operations = || {
+            RouteOperations::new(
+                &CrudOperations::Specific(vec![SpecificOperation::Read]),
+                &IDType::Int,
+            )
+        };
+        let rendered = [
+            AxumDieselTemplate {
+                name: "Post",
+                struct_name: "Post",
+                id: IDType::Int,
+                operations: operations(),
+            }
+            .render()
+            .unwrap(),
+            AxumSeaOrmTemplate {
+                name: "Post",
+                struct_name: "Post",
+                id: IDType::Int,
+                operations: operations(),
+            }
+            .render()
+            .unwrap(),
+            ActixSeaOrmTemplate {
+                name: "Post",
+                struct_name: "Post",
+                id: IDType::Int,
+                operations: operations(),
+            }
+            .render()
+            .unwrap(),
+        ];
+
+        for rendered in rendered {
+            assert!(rendered.contains("async fn get_posts("));
+            assert!(rendered.contains("async fn get_post("));
+            assert!(!rendered.contains("new_post"));
+            assert!(!rendered.contains("update_post"));
+            assert!(!rendered.contains("delete_post"));
+            assert!(!rendered.contains("NewPost"));
+            assert!(!rendered.contains("UpdatePost"));
+        }
+    }
+
+    #[test]
+    fn test_foreign_key_migration() {
+        let reference = Reference::parse("belongs_to(user)", false)
+            .unwrap()
+            .unwrap();
+        let template = SqlxUpTemplate {
+            name: "Post",
+            timestamp: Utc::now(),
+            rows: vec!["author INTEGER NOT NULL".to_string()],
+            foreign_keys: vec![ForeignKey::new("author", &reference)],
+            indexes: vec![],
+            enums: vec![],
+            id: IDType::Int,
+            database_type: DatabaseType::PostgreSQL,
+            uuid_extension: UuidExtension::default(),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains(
+            "updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),\n  FOREIGN KEY (author) REFERENCES user (id) ON DELETE CASCADE\n);"
+        ));
+        assert!(rendered.contains("CREATE INDEX post_author_idx ON post (author);"));
+        assert!(rendered.contains("SELECT manage_updated_at('post');"));
+
+        let template = DieselModelTemplate {
+            name: "Post",
+            struct_name: "Post",
+            rows: vec!["author: i32".to_string()],
+            foreign_keys: vec![ForeignKey::new("author", &reference)],
+            enums: vec![],
+            id: IDType::Int,
+            database_type: DatabaseType::PostgreSQL,
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("Identifiable, Associations, Serialize"));
+        assert!(rendered.contains("#[diesel(belongs_to(User, foreign_key = author))]"));
+    }
+
+    #[test]
+    fn test_modifier_indexes() {
+        let template = SqlxUpTemplate {
+            name: "User",
+            timestamp: Utc::now(),
+            rows: vec!["age INT NOT NULL CHECK (age >= 0)".to_string()],
+            foreign_keys: vec![],
+            indexes: vec!["age".to_string()],
+            enums: vec![],
+            id: IDType::Int,
+            database_type: DatabaseType::MySQL,
+            uuid_extension: UuidExtension::default(),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.contains("age INT NOT NULL CHECK (age >= 0),"));
+        assert!(rendered.contains("CREATE INDEX user_age_idx ON user (age);"));
+
+        let template = SqlxDownTemplate {
+            name: "User",
+            timestamp: Utc::now(),
+            indexes: vec!["age".to_string()],
+            enums: vec![],
+            database_type: DatabaseType::MySQL,
+        };
+        assert_eq!(
+            template.render().unwrap(),
+            "drop index user_age_idx on user;\ndrop table user;"
+        );
+    }
+
+    #[test]
+    fn test_enum_migration() {
+        let mut enum_type = EnumType::new(&["draft", "inReview"]);
+        enum_type.name = "post_status".to_string();
+        let template = SqlxUpTemplate {
+            name: "Post",
+            timestamp: Utc::now(),
+            rows: vec!["status post_status NOT NULL".to_string()],
+            foreign_keys: vec![],
+            indexes: vec![],
+            enums: vec![enum_type.clone()],
+            id: IDType::Int,
+            database_type: DatabaseType::PostgreSQL,
+            uuid_extension: UuidExtension::default(),
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(rendered.starts_with(
+            "CREATE TYPE post_status AS ENUM ('draft', 'in_review');\n\nCREATE TABLE post ("
+        ));
+
+        let template = SqlxDownTemplate {
+            name: "Post",
+            timestamp: Utc::now(),
+            indexes: vec![],
+            enums: vec![enum_type.clone()],
+            database_type: DatabaseType::PostgreSQL,
+        };
+        assert_eq!(
+            template.render().unwrap(),
+            "drop table post;\ndrop type post_status;"
+        );
+
+        let template = SqlxModelTemplate {
+            id: IDType::Int,
+            name: "Post",
+            struct_name: "Post",
+            rows: vec!["status: PostStatus".to_string()],
+            enums: vec![enum_type],
+        };
+        let rendered = template.render().unwrap();
+
+        assert!(
+            rendered.contains("#[sqlx(type_name = \"post_status\", rename_all = \"snake_case\")]")
+        );
+        assert!(rendered.contains("pub enum PostStatus {\n    Draft,\n    InReview,\n}"));
+    }
+
+    #[test]
+    fn test_diesel_schema() {
+        let mut enum_type = EnumType::new(&["draft", "published"]);
+        enum_type.name = "post_status".to_string();
+        let template = DieselSchemaTemplate {
+            name: "Post",
+            columns: vec![
+                "title -> Varchar".to_string(),
+                "status -> PostStatus".to_string(),
+            ],
+            foreign_keys: vec![],
+            enums: vec![enum_type],
+            id: IDType::Uuid,
+            database_type: DatabaseType::PostgreSQL,
+        };
+
+        assert_eq!(
+            template.render().unwrap(),
+            r#"diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PostStatus;
+
+    post (id) {
+        id -> Uuid,
+        title -> Varchar,
+        status -> PostStatus,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}"#
+        );
+        assert_eq!(
+            template.sql_types(),
+            vec!["    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]\n    #[diesel(postgres_type(name = \"post_status\"))]\n    pub struct PostStatus;"]
+        );
     }
 }
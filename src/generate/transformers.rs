@@ -1,49 +1,216 @@
+use super::attribute::Modifiers;
 use super::data_types::DataType;
+use crate::config::{DatabaseType, DecimalCrate};
 use convert_case::{Case, Casing};
 
 pub trait DataTypeTransformer {
     fn get_type(&self, row_type: &DataType) -> String;
     fn get_row(&self, row_type: &DataType, name: &str) -> String;
     fn get_optional_row(&self, row_type: &DataType, name: &str) -> String;
+
+    /// Constraints appended to the row, only used by migrations
+    fn get_modifiers(&self, _row_type: &DataType, _modifiers: &Modifiers, _name: &str) -> String {
+        String::new()
+    }
 }
 
-pub struct RustStruct {}
+/// Column constraints as SQL, shared by all databases
+fn get_sql_modifiers(row_type: &DataType, modifiers: &Modifiers, name: &str) -> String {
+    let mut res = String::new();
+    if modifiers.unique {
+        res.push_str(" UNIQUE");
+    }
+    if let Some(default) = &modifiers.default {
+        res.push_str(&format!(" DEFAULT {}", get_sql_default(row_type, default)));
+    }
+    if let Some(check) = get_sql_check(modifiers, name) {
+        res.push_str(&format!(" CHECK ({})", check));
+    }
+    res
+}
 
-impl DataTypeTransformer for RustStruct {
+fn get_sql_check(modifiers: &Modifiers, name: &str) -> Option<String> {
+    if modifiers.checks.is_empty() {
+        return None;
+    }
+    let checks = modifiers
+        .checks
+        .iter()
+        .map(|check| format!("{} {}", name.to_case(Case::Snake), check))
+        .collect::<Vec<_>>();
+    Some(checks.join(" AND "))
+}
+
+/// Quotes defaults of text like columns, unless they call a function like `now()`
+fn get_sql_default(row_type: &DataType, default: &str) -> String {
+    let is_text = !matches!(
+        row_type,
+        DataType::Boolean
+            | DataType::SmallInt
+            | DataType::Integer
+            | DataType::BigInt
+            | DataType::Real
+            | DataType::DoublePrecision
+            | DataType::Numeric(_, _)
+    );
+    let is_expression = default.ends_with(')') || default.starts_with('\'');
+    match is_text && !is_expression {
+        true => format!("'{}'", default.replace('\'', "''")),
+        false => default.to_string(),
+    }
+}
+
+/// Rust types that decode the same way with every driver, `None` if it depends on the driver
+fn get_common_rust_type(row_type: &DataType) -> Option<String> {
+    let rust_type = match row_type {
+        DataType::Boolean => "bool",
+        DataType::SmallInt => "i16",
+        DataType::Integer => "i32",
+        DataType::BigInt => "i64",
+        DataType::Real => "f32",
+        DataType::DoublePrecision => "f64",
+        DataType::Char(_) | DataType::VarChar(_) | DataType::Text => "String",
+        DataType::Bytea => "Vec<u8>",
+        DataType::Timestamp => "chrono::NaiveDateTime",
+        DataType::Date => "chrono::NaiveDate",
+        DataType::Time => "chrono::NaiveTime",
+        DataType::Enum(enum_type) => return Some(enum_type.rust_name()),
+        _ => return None,
+    };
+    Some(rust_type.to_string())
+}
+
+fn get_rust_row(rust_type: String, name: &str) -> String {
+    format!("{}: {}", name.to_case(Case::Snake), rust_type)
+}
+
+fn get_optional_rust_row(rust_type: String, name: &str) -> String {
+    format!("{}: Option<{}>", name.to_case(Case::Snake), rust_type)
+}
+
+pub struct SqlxStruct {
+    pub database_type: DatabaseType,
+    pub decimal: DecimalCrate,
+}
+
+impl DataTypeTransformer for SqlxStruct {
     fn get_type(&self, row_type: &DataType) -> String {
+        if let Some(rust_type) = get_common_rust_type(row_type) {
+            return rust_type;
+        }
+        match (row_type, &self.database_type) {
+            // sqlx only decodes decimals from PostgreSQL and MySQL
+            (DataType::Numeric(_, _), DatabaseType::Sqlite) => "f64".to_string(),
+            (DataType::Numeric(_, _), _) => self.decimal.rust_type().to_string(),
+            (DataType::TimestampTZ, _) => "chrono::DateTime<chrono::Utc>".to_string(),
+            (DataType::TimeTZ, DatabaseType::PostgreSQL) => {
+                "sqlx::postgres::types::PgTimeTz".to_string()
+            }
+            (DataType::Interval, DatabaseType::PostgreSQL) => {
+                "sqlx::postgres::types::PgInterval".to_string()
+            }
+            (DataType::TimeTZ | DataType::Interval, DatabaseType::MySQL) => {
+                "chrono::NaiveTime".to_string()
+            }
+            (DataType::TimeTZ | DataType::Interval, DatabaseType::Sqlite) => "String".to_string(),
+            (DataType::Jsonb, _) => "serde_json::Value".to_string(),
+            (DataType::Uuid, _) => "uuid::Uuid".to_string(),
+            (DataType::Array(element_type), _) => format!("Vec<{}>", self.get_type(element_type)),
+            _ => unreachable!("Handled by get_common_rust_type"),
+        }
+    }
+
+    fn get_row(&self, row_type: &DataType, name: &str) -> String {
+        get_rust_row(self.get_type(row_type), name)
+    }
+
+    fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
+        get_optional_rust_row(self.get_type(row_type), name)
+    }
+}
+
+/// Types matching the sql types `diesel print-schema` writes for the generated migrations
+pub struct DieselStruct {
+    pub database_type: DatabaseType,
+    pub decimal: DecimalCrate,
+}
+
+impl DataTypeTransformer for DieselStruct {
+    fn get_type(&self, row_type: &DataType) -> String {
+        match (row_type, &self.database_type) {
+            // Everything that isn't a keyword of SQLite is read as text or integer
+            (DataType::SmallInt, DatabaseType::Sqlite) => return "i32".to_string(),
+            (
+                DataType::TimestampTZ | DataType::TimeTZ | DataType::Interval | DataType::Jsonb,
+                DatabaseType::Sqlite,
+            ) => return "String".to_string(),
+            (DataType::Uuid, DatabaseType::MySQL | DatabaseType::Sqlite) => {
+                return "Vec<u8>".to_string()
+            }
+            _ => {}
+        }
+        if let Some(rust_type) = get_common_rust_type(row_type) {
+            return rust_type;
+        }
+        match (row_type, &self.database_type) {
+            (DataType::Numeric(_, _), DatabaseType::Sqlite) => "f64".to_string(),
+            (DataType::Numeric(_, _), _) => self.decimal.rust_type().to_string(),
+            (DataType::TimestampTZ, _) => "chrono::DateTime<chrono::Utc>".to_string(),
+            (DataType::TimeTZ, DatabaseType::PostgreSQL) => {
+                unreachable!("Rejected by DieselSchema::supports")
+            }
+            (DataType::TimeTZ, _) => "chrono::NaiveTime".to_string(),
+            (DataType::Interval, DatabaseType::PostgreSQL) => {
+                "diesel::pg::data_types::PgInterval".to_string()
+            }
+            (DataType::Interval, _) => "chrono::NaiveTime".to_string(),
+            (DataType::Jsonb, _) => "serde_json::Value".to_string(),
+            (DataType::Uuid, _) => "uuid::Uuid".to_string(),
+            // Elements of postgres arrays are nullable
+            (DataType::Array(element_type), _) => {
+                format!("Vec<Option<{}>>", self.get_type(element_type))
+            }
+            _ => unreachable!("Handled by get_common_rust_type"),
+        }
+    }
+
+    fn get_row(&self, row_type: &DataType, name: &str) -> String {
+        get_rust_row(self.get_type(row_type), name)
+    }
+
+    fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
+        get_optional_rust_row(self.get_type(row_type), name)
+    }
+}
+
+/// Types of sea-orm entities, the migration maps time zones and intervals to plain times
+pub struct SeaOrmStruct {
+    pub decimal: DecimalCrate,
+}
+
+impl DataTypeTransformer for SeaOrmStruct {
+    fn get_type(&self, row_type: &DataType) -> String {
+        if let Some(rust_type) = get_common_rust_type(row_type) {
+            return rust_type;
+        }
         match row_type {
-            DataType::Boolean => "bool".to_string(),
-            DataType::SmallInt => "i16".to_string(),
-            DataType::Integer => "i32".to_string(),
-            DataType::BigInt => "i64".to_string(),
-            DataType::Real => "f32".to_string(),
-            DataType::DoublePrecision => "f64".to_string(),
-            DataType::Numeric(_, _) => "f64".to_string(),
-            DataType::Char(_) => "char".to_string(),
-            DataType::VarChar(_) => "String".to_string(),
-            DataType::Text => "String".to_string(),
-            DataType::Bytea => "Vec<u8>".to_string(),
-            DataType::Timestamp => "chrono::NaiveDateTime".to_string(),
+            DataType::Numeric(_, _) => self.decimal.rust_type().to_string(),
             DataType::TimestampTZ => "chrono::DateTime<chrono::Utc>".to_string(),
-            DataType::Date => "chrono::NaiveDate".to_string(),
-            DataType::Time => "chrono::NaiveTime".to_string(),
             DataType::TimeTZ => "chrono::NaiveTime".to_string(),
-            DataType::Interval => "chrono::Duration".to_string(),
+            DataType::Interval => "String".to_string(),
             DataType::Jsonb => "serde_json::Value".to_string(),
             DataType::Uuid => "uuid::Uuid".to_string(),
+            DataType::Array(element_type) => format!("Vec<{}>", self.get_type(element_type)),
+            _ => unreachable!("Handled by get_common_rust_type"),
         }
     }
 
     fn get_row(&self, row_type: &DataType, name: &str) -> String {
-        format!("{}: {}", name.to_case(Case::Snake), self.get_type(row_type))
+        get_rust_row(self.get_type(row_type), name)
     }
 
     fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
-        format!(
-            "{}: Option<{}>",
-            name.to_case(Case::Snake),
-            self.get_type(row_type)
-        )
+        get_optional_rust_row(self.get_type(row_type), name)
     }
 }
 
@@ -73,6 +240,102 @@ impl DataTypeTransformer for PostgresMigration {
             DataType::Interval => "INTERVAL".to_string(),
             DataType::Jsonb => "JSONB".to_string(),
             DataType::Uuid => "UUID".to_string(),
+            DataType::Enum(enum_type) => enum_type.name.clone(),
+            DataType::Array(element_type) => format!("{}[]", self.get_type(element_type)),
+        }
+    }
+
+    fn get_row(&self, row_type: &DataType, name: &str) -> String {
+        format!(
+            "{} {} NOT NULL",
+            name.to_case(Case::Snake),
+            self.get_type(row_type)
+        )
+    }
+
+    fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
+        format!("{} {}", name.to_case(Case::Snake), self.get_type(row_type))
+    }
+
+    fn get_modifiers(&self, row_type: &DataType, modifiers: &Modifiers, name: &str) -> String {
+        get_sql_modifiers(row_type, modifiers, name)
+    }
+}
+
+pub struct MysqlMigration {}
+
+impl DataTypeTransformer for MysqlMigration {
+    fn get_type(&self, row_type: &DataType) -> String {
+        match row_type {
+            DataType::Boolean => "BOOLEAN".to_string(),
+            DataType::SmallInt => "SMALLINT".to_string(),
+            DataType::Integer => "INT".to_string(),
+            DataType::BigInt => "BIGINT".to_string(),
+            DataType::Real => "FLOAT".to_string(),
+            DataType::DoublePrecision => "DOUBLE".to_string(),
+            DataType::Numeric(precision, scale) => {
+                format!("DECIMAL({}, {})", precision, scale)
+            }
+            DataType::Char(length) => format!("CHAR({})", length),
+            DataType::VarChar(length) => format!("VARCHAR({})", length),
+            DataType::Text => "TEXT".to_string(),
+            DataType::Bytea => "BLOB".to_string(),
+            DataType::Timestamp => "DATETIME".to_string(),
+            DataType::TimestampTZ => "TIMESTAMP".to_string(),
+            DataType::Date => "DATE".to_string(),
+            DataType::Time => "TIME".to_string(),
+            DataType::TimeTZ => "TIME".to_string(),
+            DataType::Interval => "TIME".to_string(),
+            DataType::Jsonb => "JSON".to_string(),
+            DataType::Uuid => "BINARY(16)".to_string(),
+            DataType::Enum(enum_type) => format!("ENUM({})", enum_type.sql_values()),
+            DataType::Array(_) => "JSON".to_string(),
+        }
+    }
+
+    fn get_row(&self, row_type: &DataType, name: &str) -> String {
+        format!(
+            "{} {} NOT NULL",
+            name.to_case(Case::Snake),
+            self.get_type(row_type)
+        )
+    }
+
+    fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
+        format!("{} {}", name.to_case(Case::Snake), self.get_type(row_type))
+    }
+
+    fn get_modifiers(&self, row_type: &DataType, modifiers: &Modifiers, name: &str) -> String {
+        get_sql_modifiers(row_type, modifiers, name)
+    }
+}
+
+pub struct SqliteMigration {}
+
+impl DataTypeTransformer for SqliteMigration {
+    fn get_type(&self, row_type: &DataType) -> String {
+        match row_type {
+            DataType::Boolean => "BOOLEAN".to_string(),
+            DataType::SmallInt => "INTEGER".to_string(),
+            DataType::Integer => "INTEGER".to_string(),
+            DataType::BigInt => "BIGINT".to_string(),
+            DataType::Real => "REAL".to_string(),
+            DataType::DoublePrecision => "DOUBLE".to_string(),
+            DataType::Numeric(_, _) => "NUMERIC".to_string(),
+            DataType::Char(_) => "TEXT".to_string(),
+            DataType::VarChar(_) => "TEXT".to_string(),
+            DataType::Text => "TEXT".to_string(),
+            DataType::Bytea => "BLOB".to_string(),
+            DataType::Timestamp => "TIMESTAMP".to_string(),
+            DataType::TimestampTZ => "TEXT".to_string(),
+            DataType::Date => "DATE".to_string(),
+            DataType::Time => "TIME".to_string(),
+            DataType::TimeTZ => "TEXT".to_string(),
+            DataType::Interval => "TEXT".to_string(),
+            DataType::Jsonb => "TEXT".to_string(),
+            DataType::Uuid => "BLOB".to_string(),
+            DataType::Enum(_) => "TEXT".to_string(),
+            DataType::Array(_) => "TEXT".to_string(),
         }
     }
 
@@ -87,4 +350,291 @@ impl DataTypeTransformer for PostgresMigration {
     fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
         format!("{} {}", name.to_case(Case::Snake), self.get_type(row_type))
     }
+
+    fn get_modifiers(&self, row_type: &DataType, modifiers: &Modifiers, name: &str) -> String {
+        get_sql_modifiers(row_type, modifiers, name)
+    }
+}
+
+/// Columns of the `diesel::table!` macro, named like `diesel print-schema` would
+pub struct DieselSchema {
+    pub database_type: DatabaseType,
+}
+
+impl DieselSchema {
+    /// Diesel has no sql type for postgres' TIMETZ, MySQL stores it as a plain TIME
+    pub fn supports(&self, row_type: &DataType) -> bool {
+        match row_type {
+            DataType::TimeTZ => self.database_type != DatabaseType::PostgreSQL,
+            DataType::Array(element_type) => self.supports(element_type),
+            _ => true,
+        }
+    }
+}
+
+impl DataTypeTransformer for DieselSchema {
+    fn get_type(&self, row_type: &DataType) -> String {
+        let sql_type = match (row_type, &self.database_type) {
+            (DataType::Boolean, _) => "Bool",
+            (DataType::SmallInt, DatabaseType::PostgreSQL) => "Int2",
+            (DataType::SmallInt, DatabaseType::MySQL) => "Smallint",
+            (DataType::Integer, DatabaseType::PostgreSQL) => "Int4",
+            (DataType::SmallInt | DataType::Integer, _) => "Integer",
+            (DataType::BigInt, DatabaseType::PostgreSQL) => "Int8",
+            (DataType::BigInt, DatabaseType::MySQL) => "Bigint",
+            (DataType::BigInt, DatabaseType::Sqlite) => "BigInt",
+            (DataType::Real, DatabaseType::PostgreSQL) => "Float4",
+            (DataType::Real, _) => "Float",
+            (DataType::DoublePrecision, DatabaseType::PostgreSQL) => "Float8",
+            (DataType::DoublePrecision | DataType::Numeric(_, _), DatabaseType::Sqlite) => "Double",
+            (DataType::DoublePrecision, _) => "Double",
+            (DataType::Numeric(_, _), _) => "Numeric",
+            (DataType::Char(_), DatabaseType::PostgreSQL) => "Bpchar",
+            (DataType::Char(_), DatabaseType::MySQL) => "Char",
+            (DataType::VarChar(_), DatabaseType::PostgreSQL | DatabaseType::MySQL) => "Varchar",
+            (DataType::Char(_) | DataType::VarChar(_) | DataType::Text, _) => "Text",
+            (DataType::Bytea, DatabaseType::PostgreSQL) => "Bytea",
+            (DataType::Bytea, DatabaseType::MySQL) => "Blob",
+            (DataType::Bytea, DatabaseType::Sqlite) => "Binary",
+            (DataType::Timestamp, DatabaseType::MySQL) => "Datetime",
+            (DataType::Timestamp, _) => "Timestamp",
+            (DataType::TimestampTZ, DatabaseType::PostgreSQL) => "Timestamptz",
+            (DataType::TimestampTZ, DatabaseType::MySQL) => "Timestamp",
+            (DataType::Date, _) => "Date",
+            (DataType::TimeTZ, DatabaseType::PostgreSQL) => {
+                unreachable!("Rejected by DieselSchema::supports")
+            }
+            (DataType::Time, _)
+            | (DataType::TimeTZ, DatabaseType::MySQL)
+            | (DataType::Interval, DatabaseType::MySQL) => "Time",
+            (DataType::Interval, DatabaseType::PostgreSQL) => "Interval",
+            (DataType::Jsonb, DatabaseType::PostgreSQL) => "Jsonb",
+            (DataType::Jsonb, DatabaseType::MySQL) => "Json",
+            (DataType::Uuid, DatabaseType::PostgreSQL) => "Uuid",
+            (DataType::Uuid, _) => "Binary",
+            // Stored as text by the SQLite migrations
+            (
+                DataType::TimestampTZ | DataType::TimeTZ | DataType::Interval | DataType::Jsonb,
+                _,
+            ) => "Text",
+            // Postgres enums are declared in `schema::sql_types`, the others use the type of the derive
+            (DataType::Enum(enum_type), DatabaseType::PostgreSQL) => return enum_type.rust_name(),
+            (DataType::Enum(enum_type), _) => return format!("{}Mapping", enum_type.rust_name()),
+            (DataType::Array(element_type), _) => {
+                return format!("Array<Nullable<{}>>", self.get_type(element_type))
+            }
+        };
+        sql_type.to_string()
+    }
+
+    fn get_row(&self, row_type: &DataType, name: &str) -> String {
+        format!(
+            "{} -> {}",
+            name.to_case(Case::Snake),
+            self.get_type(row_type)
+        )
+    }
+
+    fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
+        format!(
+            "{} -> Nullable<{}>",
+            name.to_case(Case::Snake),
+            self.get_type(row_type)
+        )
+    }
+}
+
+/// Column definitions for sea-orm-migration, the backend specific SQL is left to sea-query
+pub struct SeaOrmMigration {}
+
+impl DataTypeTransformer for SeaOrmMigration {
+    fn get_type(&self, row_type: &DataType) -> String {
+        match row_type {
+            DataType::Boolean => "boolean()".to_string(),
+            DataType::SmallInt => "small_integer()".to_string(),
+            DataType::Integer => "integer()".to_string(),
+            DataType::BigInt => "big_integer()".to_string(),
+            DataType::Real => "float()".to_string(),
+            DataType::DoublePrecision => "double()".to_string(),
+            // a length of 0 means none was given, so sea-query's default is used
+            DataType::Numeric(0, _) => "decimal()".to_string(),
+            DataType::Numeric(precision, scale) => {
+                format!("decimal_len({}, {})", precision, scale)
+            }
+            DataType::Char(0) => "char()".to_string(),
+            DataType::Char(length) => format!("char_len({})", length),
+            DataType::VarChar(0) => "string()".to_string(),
+            DataType::VarChar(length) => format!("string_len({})", length),
+            DataType::Text => "text()".to_string(),
+            DataType::Bytea => "binary()".to_string(),
+            DataType::Timestamp => "timestamp()".to_string(),
+            DataType::TimestampTZ => "timestamp_with_time_zone()".to_string(),
+            DataType::Date => "date()".to_string(),
+            DataType::Time => "time()".to_string(),
+            DataType::TimeTZ => "time()".to_string(),
+            DataType::Interval => "interval(None, None)".to_string(),
+            DataType::Jsonb => "json_binary()".to_string(),
+            DataType::Uuid => "uuid()".to_string(),
+            DataType::Enum(enum_type) => format!(
+                "enumeration(Alias::new({:?}), [{}])",
+                enum_type.name,
+                enum_type
+                    .values
+                    .iter()
+                    .map(|value| format!("Alias::new({:?})", value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            DataType::Array(element_type) => {
+                format!("array({})", sea_orm_column_type(element_type))
+            }
+        }
+    }
+
+    fn get_row(&self, row_type: &DataType, name: &str) -> String {
+        format!(
+            "ColumnDef::new(Columns::{}).{}.not_null()",
+            name.to_case(Case::Pascal),
+            self.get_type(row_type)
+        )
+    }
+
+    fn get_optional_row(&self, row_type: &DataType, name: &str) -> String {
+        format!(
+            "ColumnDef::new(Columns::{}).{}.null()",
+            name.to_case(Case::Pascal),
+            self.get_type(row_type)
+        )
+    }
+
+    fn get_modifiers(&self, row_type: &DataType, modifiers: &Modifiers, name: &str) -> String {
+        let mut res = String::new();
+        if modifiers.unique {
+            res.push_str(".unique_key()");
+        }
+        if let Some(default) = &modifiers.default {
+            res.push_str(&format!(
+                ".default(Expr::cust({:?}))",
+                get_sql_default(row_type, default)
+            ));
+        }
+        if let Some(check) = get_sql_check(modifiers, name) {
+            res.push_str(&format!(".check(Expr::cust({:?}))", check));
+        }
+        res
+    }
+}
+
+/// `ColumnType` of sea-query, used for the elements of array columns
+fn sea_orm_column_type(row_type: &DataType) -> String {
+    match row_type {
+        DataType::Boolean => "ColumnType::Boolean".to_string(),
+        DataType::SmallInt => "ColumnType::SmallInteger".to_string(),
+        DataType::Integer => "ColumnType::Integer".to_string(),
+        DataType::BigInt => "ColumnType::BigInteger".to_string(),
+        DataType::Real => "ColumnType::Float".to_string(),
+        DataType::DoublePrecision => "ColumnType::Double".to_string(),
+        DataType::Numeric(0, _) => "ColumnType::Decimal(None)".to_string(),
+        DataType::Numeric(precision, scale) => {
+            format!("ColumnType::Decimal(Some(({}, {})))", precision, scale)
+        }
+        DataType::Char(0) => "ColumnType::Char(None)".to_string(),
+        DataType::Char(length) => format!("ColumnType::Char(Some({}))", length),
+        DataType::VarChar(0) => "ColumnType::String(None)".to_string(),
+        DataType::VarChar(length) => format!("ColumnType::String(Some({}))", length),
+        DataType::Text => "ColumnType::Text".to_string(),
+        DataType::Bytea => "ColumnType::Binary(BlobSize::Blob(None))".to_string(),
+        DataType::Timestamp => "ColumnType::Timestamp".to_string(),
+        DataType::TimestampTZ => "ColumnType::TimestampWithTimeZone".to_string(),
+        DataType::Date => "ColumnType::Date".to_string(),
+        DataType::Time | DataType::TimeTZ => "ColumnType::Time".to_string(),
+        DataType::Interval => "ColumnType::Interval(None, None)".to_string(),
+        DataType::Jsonb => "ColumnType::JsonBinary".to_string(),
+        DataType::Uuid => "ColumnType::Uuid".to_string(),
+        DataType::Enum(_) | DataType::Array(_) => {
+            unreachable!("Arrays of arrays or enums are rejected when parsing")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_struct_types_per_driver() {
+        let sqlx = SqlxStruct {
+            database_type: DatabaseType::PostgreSQL,
+            decimal: DecimalCrate::BigDecimal,
+        };
+        assert_eq!(
+            sqlx.get_type(&DataType::Numeric(10, 2)),
+            "bigdecimal::BigDecimal"
+        );
+        assert_eq!(sqlx.get_type(&DataType::Char(2)), "String");
+        assert_eq!(
+            sqlx.get_type(&DataType::Interval),
+            "sqlx::postgres::types::PgInterval"
+        );
+        assert_eq!(
+            sqlx.get_type(&DataType::TimeTZ),
+            "sqlx::postgres::types::PgTimeTz"
+        );
+
+        let diesel = DieselStruct {
+            database_type: DatabaseType::PostgreSQL,
+            decimal: DecimalCrate::RustDecimal,
+        };
+        assert_eq!(
+            diesel.get_type(&DataType::Numeric(10, 2)),
+            "rust_decimal::Decimal"
+        );
+        assert_eq!(
+            diesel.get_type(&DataType::Array(Box::new(DataType::Text))),
+            "Vec<Option<String>>"
+        );
+
+        let diesel = DieselStruct {
+            database_type: DatabaseType::Sqlite,
+            decimal: DecimalCrate::RustDecimal,
+        };
+        assert_eq!(diesel.get_type(&DataType::Uuid), "Vec<u8>");
+        assert_eq!(diesel.get_type(&DataType::TimestampTZ), "String");
+
+        let diesel = DieselStruct {
+            database_type: DatabaseType::MySQL,
+            decimal: DecimalCrate::RustDecimal,
+        };
+        assert_eq!(diesel.get_type(&DataType::TimeTZ), "chrono::NaiveTime");
+
+        let sea_orm = SeaOrmStruct {
+            decimal: DecimalCrate::RustDecimal,
+        };
+        assert_eq!(
+            sea_orm.get_optional_row(&DataType::Numeric(10, 2), "price"),
+            "price: Option<rust_decimal::Decimal>"
+        );
+    }
+
+    #[test]
+    fn test_diesel_time_tz() {
+        let schema = DieselSchema {
+            database_type: DatabaseType::PostgreSQL,
+        };
+        assert!(!schema.supports(&DataType::TimeTZ));
+        assert!(!schema.supports(&DataType::Array(Box::new(DataType::TimeTZ))));
+        assert!(schema.supports(&DataType::Time));
+
+        let schema = DieselSchema {
+            database_type: DatabaseType::MySQL,
+        };
+        assert!(schema.supports(&DataType::TimeTZ));
+        assert_eq!(schema.get_type(&DataType::TimeTZ), "Time");
+
+        let schema = DieselSchema {
+            database_type: DatabaseType::Sqlite,
+        };
+        assert!(schema.supports(&DataType::TimeTZ));
+        assert_eq!(schema.get_type(&DataType::TimeTZ), "Text");
+    }
 }
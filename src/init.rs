@@ -1,13 +1,10 @@
-use std::{
-    fs,
-    path::{Path, PathBuf},
-    time::Duration,
-};
+use std::{path::Path, time::Duration};
 
 use crate::{
     add::{add_addon, Features},
-    config::{ApiFramework, Config, ConfigBuilder, Database, DatabaseDriver, DatabaseType},
+    config::{ApiFramework, ConfigBuilder, Database, DatabaseDriver, DatabaseType},
     generate::FromTerm,
+    starter::{Starter, StarterSource, StarterVariables},
 };
 use anyhow::{Context, Result};
 use clap::{Parser, ValueEnum};
@@ -33,6 +30,18 @@ pub struct InitArgs {
 
     #[clap(short = 'd', long, value_enum)]
     pub database_driver: Option<DatabaseDriver>,
+
+    #[clap(long, conflicts_with = "starter")]
+    /// Clone the starter from the schmiede repository instead of using the bundled one
+    pub remote: bool,
+
+    #[clap(short, long)]
+    /// Local folder or git url of a custom starter containing a schmiede-starter.toml
+    pub starter: Option<String>,
+
+    #[clap(short, long = "ref")]
+    /// Branch, tag or commit of the starter repository to use
+    pub reference: Option<String>,
 }
 
 pub fn init_starter(args: InitArgs, term: Term, theme: ColorfulTheme) -> Result<()> {
@@ -48,9 +57,22 @@ pub fn init_starter(args: InitArgs, term: Term, theme: ColorfulTheme) -> Result<
         anyhow::bail!("Project folder already exists");
     }
 
-    let api_framework = match args.api_framework {
-        Some(a) => a,
-        None => ApiFramework::from_term(&term, &theme).context("Failed to get api framework")?,
+    let variables = StarterVariables::new(&project_name)?;
+
+    let custom_starter = match &args.starter {
+        Some(starter) => Some(Starter::load(&StarterSource::new(
+            starter,
+            args.reference.clone(),
+        )?)?),
+        None => None,
+    };
+
+    let api_framework = match (args.api_framework, &custom_starter) {
+        (Some(a), _) => a,
+        (None, Some(starter)) => starter.manifest.api_framework.clone(),
+        (None, None) => {
+            ApiFramework::from_term(&term, &theme).context("Failed to get api framework")?
+        }
     };
 
     let database: Option<Database> = match args.database_type {
@@ -65,34 +87,51 @@ pub fn init_starter(args: InitArgs, term: Term, theme: ColorfulTheme) -> Result<
         None => {
             let database =
                 DatabaseType::from_term(&term, &theme).context("Failed to get database")?;
-            if database.is_none() {
-                None
-            } else {
-                let driver = DatabaseDriver::from_term(&term, &theme)
-                    .context("Failed to get database driver")?;
-                Some(Database::new(database.unwrap(), driver))
+            match database {
+                Some(database) => {
+                    let driver = DatabaseDriver::from_term(&term, &theme)
+                        .context("Failed to get database driver")?;
+                    Some(Database::new(database, driver))
+                }
+                None => None,
             }
         }
     };
 
-    let config = ConfigBuilder::new()
+    let mut config = ConfigBuilder::new()
         .api_framework(api_framework)
-        .database(database.clone())
+        .database(database)
         .build();
 
     let pb_starter = ProgressBar::new_spinner();
     pb_starter.set_message("Creating project...");
     pb_starter.enable_steady_tick(Duration::from_millis(120));
-    let path = clone_starter(&config, &project_name)?;
+    let mut starter = match custom_starter {
+        Some(starter) => starter,
+        None if args.remote => Starter::load(&StarterSource::remote(
+            &config.api_framework,
+            args.reference,
+        ))?,
+        None if args.reference.is_some() => {
+            anyhow::bail!("A ref can only be used together with --starter or --remote")
+        }
+        None => Starter::bundled(&config.api_framework)?,
+    };
+    starter.manifest.check(&config)?;
+    starter.render(&variables);
+
+    let path = Path::new(&project_name).to_path_buf();
+    starter.write(&path)?;
     pb_starter.set_message("Creating project ✓");
     pb_starter.finish();
 
     let pb_addons = ProgressBar::new_spinner();
 
-    if database.is_some() {
+    // add_addon only installs a database the config doesn't have yet and records it again
+    if let Some(database) = config.database.take() {
         pb_addons.set_message("Preparing addons...");
         pb_addons.enable_steady_tick(Duration::from_millis(120));
-        add_addon(Features::Database(database.unwrap()), false)?;
+        add_addon(Features::Database(database), &path, &mut config)?;
         pb_addons.set_message("Preparing addons ✓");
         pb_addons.finish();
     }
@@ -100,20 +139,3 @@ pub fn init_starter(args: InitArgs, term: Term, theme: ColorfulTheme) -> Result<
     config.create_config_toml(&path)?;
     Ok(())
 }
-
-fn clone_starter(config: &Config, project_name: &str) -> Result<PathBuf> {
-    let temp_dir = Path::new("./temporary");
-    let mut fetch_options = git2::FetchOptions::new();
-    fetch_options.depth(1);
-    git2::build::RepoBuilder::new()
-        .fetch_options(fetch_options)
-        .clone("https://github.com/LeNei/schmiede", temp_dir)
-        .context("Failed to clone repo")?;
-
-    let source_folder = temp_dir.join(format!("{}/{}", "starters", config.api_framework));
-
-    fs::rename(source_folder, project_name).context("Failed to get starter")?;
-
-    fs::remove_dir_all(temp_dir).context("Failed to remove temporary folder")?;
-    Ok(Path::new(project_name).to_path_buf())
-}
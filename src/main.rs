@@ -1,17 +1,24 @@
 mod add;
 mod config;
+mod files;
 mod generate;
 mod init;
+mod starter;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use config::Config;
 use console::Term;
 use dialoguer::theme::ColorfulTheme;
+use std::path::Path;
 
 #[derive(Parser)]
 struct Args {
     #[clap(subcommand)]
     cmd: Option<Commands>,
+    /// Print the changes of generate and add as diffs without writing them
+    #[clap(long, global = true)]
+    dry_run: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -28,9 +35,29 @@ fn main() -> Result<()> {
     let theme = ColorfulTheme::default();
 
     match args.cmd {
-        Some(Commands::Generate(args)) => generate::generate_files(args, term, theme),
-        Some(Commands::Init(args)) => init::init_starter(args, term, theme),
-        Some(Commands::Add(args)) => add::add_addon(args, true),
-        None => Ok(()),
+        Some(Commands::Init(_)) if args.dry_run => {
+            anyhow::bail!("--dry-run is only supported by generate and add")
+        }
+        Some(Commands::Init(_)) | None => (),
+        // Nothing is written until generate and add succeeded
+        Some(_) => files::stage(),
     }
+
+    match args.cmd {
+        Some(Commands::Generate(args)) => generate::generate_files(args, term, theme)?,
+        Some(Commands::Init(args)) => init::init_starter(args, term, theme)?,
+        Some(Commands::Add(args)) => {
+            let mut config = Config::from_file()?;
+            add::add_addon(args, Path::new("."), &mut config)?;
+            config.update_config()?
+        }
+        None => (),
+    }
+    match args.dry_run {
+        true => files::print_changes(),
+        false => {
+            files::commit().context("Failed to write the changes, the project was restored")?
+        }
+    }
+    Ok(())
 }
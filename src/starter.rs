@@ -0,0 +1,506 @@
+use crate::config::{ApiFramework, Config};
+use anyhow::{Context, Result};
+use include_dir::{include_dir, Dir, DirEntry};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+static STARTERS: Dir = include_dir!("$CARGO_MANIFEST_DIR/starters");
+
+const REMOTE_URL: &str = "https://github.com/LeNei/schmiede";
+const MANIFEST_FILE: &str = "schmiede-starter.toml";
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StarterManifest {
+    pub api_framework: ApiFramework,
+    #[serde(default)]
+    pub addons: Vec<Addon>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Addon {
+    Database,
+}
+
+impl StarterManifest {
+    fn from_bytes(contents: &[u8]) -> Result<Self> {
+        let contents = std::str::from_utf8(contents).context("Manifest is not valid utf-8")?;
+        toml::from_str(contents).context(format!("Failed to parse {}", MANIFEST_FILE))
+    }
+
+    pub fn check(&self, config: &Config) -> Result<()> {
+        if self.api_framework != config.api_framework {
+            anyhow::bail!(
+                "Starter is made for {} but {} was selected",
+                self.api_framework,
+                config.api_framework
+            );
+        }
+
+        if config.database.is_some() && !self.addons.contains(&Addon::Database) {
+            anyhow::bail!("Starter does not support the database addon");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StarterSource {
+    Local(PathBuf),
+    Git {
+        url: String,
+        reference: Option<String>,
+        subdir: Option<PathBuf>,
+    },
+}
+
+impl StarterSource {
+    pub fn new(starter: &str, reference: Option<String>) -> Result<Self> {
+        let path = Path::new(starter);
+        match reference {
+            None if path.is_dir() => Ok(Self::Local(path.to_path_buf())),
+            // The ref is checked out in a clone, the folder itself stays as it is
+            Some(reference) if path.is_dir() => match git2::Repository::open(path) {
+                Ok(_) => Ok(Self::Git {
+                    url: starter.to_string(),
+                    reference: Some(reference),
+                    subdir: None,
+                }),
+                Err(_) => anyhow::bail!(
+                    "--ref {} can only be used with a git repository, {} is a plain folder",
+                    reference,
+                    starter
+                ),
+            },
+            reference => Ok(Self::Git {
+                url: starter.to_string(),
+                reference,
+                subdir: None,
+            }),
+        }
+    }
+
+    pub fn remote(api_framework: &ApiFramework, reference: Option<String>) -> Self {
+        Self::Git {
+            url: REMOTE_URL.to_string(),
+            reference,
+            subdir: Some(Path::new("starters").join(api_framework.to_string())),
+        }
+    }
+}
+
+pub struct StarterVariables {
+    pub project_name: String,
+    pub crate_name: String,
+    pub authors: Vec<String>,
+}
+
+impl StarterVariables {
+    pub fn new(project_name: &str) -> Result<Self> {
+        let valid = project_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if project_name.is_empty()
+            || !valid
+            || project_name.starts_with(|c: char| c.is_ascii_digit())
+        {
+            anyhow::bail!(
+                "{} is not a valid project name. Use letters, numbers, - and _",
+                project_name
+            );
+        }
+
+        Ok(Self {
+            project_name: project_name.to_string(),
+            crate_name: project_name.replace('-', "_"),
+            authors: git_author().into_iter().collect(),
+        })
+    }
+
+    fn render(&self, contents: &str) -> String {
+        let authors = self
+            .authors
+            .iter()
+            .map(|author| format!("\"{}\"", author))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        contents
+            .replace("{{ project_name }}", &self.project_name)
+            .replace("{{ crate_name }}", &self.crate_name)
+            .replace("{{ authors }}", &authors)
+    }
+}
+
+fn git_author() -> Option<String> {
+    let config = git2::Config::open_default().ok()?;
+    let name = config.get_string("user.name").ok()?;
+    match config.get_string("user.email") {
+        Ok(email) => Some(format!("{} <{}>", name, email)),
+        Err(_) => Some(name),
+    }
+}
+
+pub struct Starter {
+    pub manifest: StarterManifest,
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl Starter {
+    pub fn bundled(api_framework: &ApiFramework) -> Result<Self> {
+        let dir = STARTERS
+            .get_dir(api_framework.to_string())
+            .context(format!("No starter found for {}", api_framework))?;
+
+        let mut files = vec![];
+        collect_bundled(dir, dir.path(), &mut files)?;
+        Self::from_files(files)
+    }
+
+    pub fn load(source: &StarterSource) -> Result<Self> {
+        match source {
+            StarterSource::Local(path) => Self::from_files(collect_local(path)?),
+            StarterSource::Git {
+                url,
+                reference,
+                subdir,
+            } => {
+                let temp_dir = tempfile::tempdir().context("Failed to create temporary folder")?;
+                clone_repo(url, reference.as_deref(), temp_dir.path())?;
+
+                let root = match subdir {
+                    Some(subdir) => temp_dir.path().join(subdir),
+                    None => temp_dir.path().to_path_buf(),
+                };
+                Self::from_files(collect_local(&root)?)
+            }
+        }
+    }
+
+    fn from_files(mut files: Vec<(PathBuf, Vec<u8>)>) -> Result<Self> {
+        let position = files
+            .iter()
+            .position(|(path, _)| path == Path::new(MANIFEST_FILE))
+            .context(format!("Starter is missing {}", MANIFEST_FILE))?;
+        let (_, manifest) = files.remove(position);
+
+        Ok(Self {
+            manifest: StarterManifest::from_bytes(&manifest)?,
+            files,
+        })
+    }
+
+    pub fn render(&mut self, variables: &StarterVariables) {
+        for (_, contents) in self.files.iter_mut() {
+            if let Ok(text) = std::str::from_utf8(contents) {
+                *contents = variables.render(text).into_bytes();
+            }
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        for (file_path, contents) in self.files.iter() {
+            let file_path = path.join(file_path);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent).context("Failed to create starter folder")?;
+            }
+            fs::write(&file_path, contents)
+                .context(format!("Failed to write file: {}", file_path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+fn collect_bundled(dir: &Dir, root: &Path, files: &mut Vec<(PathBuf, Vec<u8>)>) -> Result<()> {
+    for entry in dir.entries() {
+        match entry {
+            DirEntry::Dir(dir) => collect_bundled(dir, root, files)?,
+            DirEntry::File(file) => files.push((
+                file.path().strip_prefix(root)?.to_path_buf(),
+                file.contents().to_vec(),
+            )),
+        }
+    }
+    Ok(())
+}
+
+fn collect_local(root: &Path) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    if !root.is_dir() {
+        anyhow::bail!("Starter folder not found: {}", root.display());
+    }
+
+    let mut files = vec![];
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+    {
+        let entry = entry.context("Failed to read starter")?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let contents = fs::read(entry.path())
+            .context(format!("Failed to read file: {}", entry.path().display()))?;
+        files.push((entry.path().strip_prefix(root)?.to_path_buf(), contents));
+    }
+    Ok(files)
+}
+
+/// Clones only the latest commit unless the ref is a commit sha that needs the full history
+fn clone_repo(url: &str, reference: Option<&str>, path: &Path) -> Result<()> {
+    let Some(reference) = reference else {
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.depth(1);
+        git2::build::RepoBuilder::new()
+            .fetch_options(fetch_options)
+            .clone(url, path)
+            .context("Failed to clone repo")?;
+        return Ok(());
+    };
+
+    let repo = git2::Repository::init(path).context("Failed to clone repo")?;
+    let mut remote = repo.remote("origin", url).context("Failed to clone repo")?;
+    let branch = format!("refs/remotes/origin/{}", reference);
+    let tag = format!("refs/tags/{}", reference);
+    fetch(
+        &mut remote,
+        &[
+            format!("+refs/heads/{}:{}", reference, branch),
+            format!("+{}:{}", tag, tag),
+        ],
+        1,
+    )?;
+
+    let object = match repo
+        .revparse_single(&branch)
+        .or_else(|_| repo.revparse_single(&tag))
+    {
+        Ok(object) => object,
+        Err(_) => {
+            fetch(
+                &mut remote,
+                &[
+                    "+refs/heads/*:refs/remotes/origin/*".to_string(),
+                    "+refs/tags/*:refs/tags/*".to_string(),
+                ],
+                0,
+            )?;
+            repo.revparse_single(reference)
+                .context(format!("Failed to find ref {}", reference))?
+        }
+    };
+    let commit = object
+        .peel_to_commit()
+        .context(format!("Ref {} does not point to a commit", reference))?;
+
+    repo.checkout_tree(
+        commit.as_object(),
+        Some(git2::build::CheckoutBuilder::new().force()),
+    )
+    .context("Failed to checkout ref")?;
+    repo.set_head_detached(commit.id())
+        .context("Failed to checkout ref")?;
+    Ok(())
+}
+
+/// Fetches the refspecs, a depth of 0 fetches the whole history
+fn fetch(remote: &mut git2::Remote, refspecs: &[String], depth: i32) -> Result<()> {
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.depth(depth);
+    remote
+        .fetch(refspecs, Some(&mut fetch_options), None)
+        .context("Failed to fetch repo")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn write_starter(path: &Path, main: &str) {
+        fs::create_dir_all(path.join("src")).unwrap();
+        fs::write(
+            path.join(MANIFEST_FILE),
+            "api_framework = \"axum\"\naddons = [\"database\"]\n",
+        )
+        .unwrap();
+        fs::write(path.join("src/main.rs"), main).unwrap();
+    }
+
+    fn commit_all(repo: &git2::Repository, message: &str) -> git2::Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bundled_starters() {
+        let options = ["axum", "actix"];
+
+        for option in options.iter() {
+            let framework = ApiFramework::from_str(option).unwrap();
+            let starter = Starter::bundled(&framework).unwrap();
+            assert_eq!(starter.manifest.api_framework, framework);
+            assert!(starter
+                .files
+                .iter()
+                .any(|(path, _)| path == Path::new("Cargo.toml")));
+        }
+    }
+
+    #[test]
+    fn test_render_bundled_starters() {
+        let options = ["axum", "actix"];
+        let variables = StarterVariables {
+            project_name: "my-project".to_string(),
+            crate_name: "my_project".to_string(),
+            authors: vec!["Jane Doe <jane@example.com>".to_string()],
+        };
+
+        for option in options.iter() {
+            let mut starter = Starter::bundled(&ApiFramework::from_str(option).unwrap()).unwrap();
+            starter.render(&variables);
+
+            let file = |name: &str| {
+                let (_, contents) = starter
+                    .files
+                    .iter()
+                    .find(|(path, _)| path == Path::new(name))
+                    .unwrap();
+                String::from_utf8(contents.clone()).unwrap()
+            };
+
+            let manifest = file("Cargo.toml").parse::<toml::Table>().unwrap();
+            assert_eq!(manifest["package"]["name"].as_str(), Some("my-project"));
+            assert_eq!(manifest["bin"][0]["name"].as_str(), Some("my-project"));
+            assert_eq!(
+                manifest["package"]["authors"][0].as_str(),
+                Some("Jane Doe <jane@example.com>")
+            );
+
+            let main = file("src/main.rs");
+            assert!(main.contains("use my_project::startup::build;"));
+            assert!(main.contains("get_subscriber(\"my-project\".into()"));
+            assert!(!main.contains("{{"));
+        }
+    }
+
+    #[test]
+    fn test_project_name_validation() {
+        assert!(StarterVariables::new("my-project_2").is_ok());
+        assert!(StarterVariables::new("my project").is_err());
+        assert!(StarterVariables::new("2project").is_err());
+        assert!(StarterVariables::new("").is_err());
+    }
+
+    #[test]
+    fn test_manifest_check() {
+        let manifest = StarterManifest::from_bytes(b"api_framework = \"axum\"").unwrap();
+        let mut config = Config::default();
+        assert!(manifest.check(&config).is_ok());
+
+        config.database = Some(crate::config::Database::new(
+            crate::config::DatabaseType::PostgreSQL,
+            crate::config::DatabaseDriver::Sqlx,
+        ));
+        assert!(manifest.check(&config).is_err());
+    }
+
+    #[test]
+    fn test_load_local_starter() {
+        let dir = tempfile::tempdir().unwrap();
+        write_starter(dir.path(), "fn main() {}");
+
+        let source = StarterSource::new(dir.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(source, StarterSource::Local(dir.path().to_path_buf()));
+
+        let starter = Starter::load(&source).unwrap();
+        assert_eq!(starter.manifest.addons, vec![Addon::Database]);
+        assert_eq!(
+            starter.files,
+            vec![(PathBuf::from("src/main.rs"), b"fn main() {}".to_vec())]
+        );
+
+        let target = tempfile::tempdir().unwrap();
+        starter.write(target.path()).unwrap();
+        assert!(target.path().join("src/main.rs").is_file());
+        assert!(!target.path().join(MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn test_load_git_starter_with_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+
+        write_starter(dir.path(), "fn main() {}");
+        let first = commit_all(&repo, "first");
+        repo.tag_lightweight("v1", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        repo.branch("pinned", &repo.find_commit(first).unwrap(), false)
+            .unwrap();
+
+        write_starter(dir.path(), "fn main() { println!(\"v2\"); }");
+        commit_all(&repo, "second");
+
+        let url = format!("file://{}", dir.path().display());
+        let latest = Starter::load(&StarterSource::new(&url, None).unwrap()).unwrap();
+        assert_eq!(
+            latest.files[0].1,
+            b"fn main() { println!(\"v2\"); }".to_vec()
+        );
+
+        for reference in ["v1".to_string(), "pinned".to_string(), first.to_string()] {
+            let pinned =
+                Starter::load(&StarterSource::new(&url, Some(reference)).unwrap()).unwrap();
+            assert_eq!(
+                pinned.files,
+                vec![(PathBuf::from("src/main.rs"), b"fn main() {}".to_vec())]
+            );
+        }
+    }
+
+    #[test]
+    fn test_local_starter_with_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        write_starter(dir.path(), "fn main() {}");
+        let starter = dir.path().to_str().unwrap();
+        assert!(StarterSource::new(starter, Some("v1".to_string())).is_err());
+
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let first = commit_all(&repo, "first");
+        repo.tag_lightweight("v1", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        write_starter(dir.path(), "fn main() { println!(\"v2\"); }");
+
+        let pinned =
+            Starter::load(&StarterSource::new(starter, Some("v1".to_string())).unwrap()).unwrap();
+        assert_eq!(
+            pinned.files,
+            vec![(PathBuf::from("src/main.rs"), b"fn main() {}".to_vec())]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("src/main.rs")).unwrap(),
+            "fn main() { println!(\"v2\"); }"
+        );
+    }
+}
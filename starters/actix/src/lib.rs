@@ -0,0 +1,4 @@
+pub mod config;
+pub mod response;
+pub mod routes;
+pub mod startup;
@@ -0,0 +1,12 @@
+use {{ crate_name }}::config::get_configuration;
+use {{ crate_name }}::config::logging::{get_subscriber, init_subscriber};
+use {{ crate_name }}::startup::build;
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    let subscriber = get_subscriber("{{ project_name }}".into(), "info".into(), std::io::stdout);
+    init_subscriber(subscriber);
+
+    let configuration = get_configuration().expect("Failed to read configuration.");
+    build(configuration.clone()).await
+}
@@ -0,0 +1,61 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt::Display;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ErrorMessage {
+    pub status: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub struct ErrorResponse(StatusCode, ErrorMessage);
+
+impl Default for ErrorResponse {
+    fn default() -> Self {
+        ErrorResponse(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorMessage {
+                status: "fail",
+                message: "Internal server error".to_string(),
+            },
+        )
+    }
+}
+
+impl Display for ErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.1.message)
+    }
+}
+
+impl ResponseError for ErrorResponse {
+    fn status_code(&self) -> StatusCode {
+        self.0
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.0).json(&self.1)
+    }
+}
+
+impl ErrorResponse {
+    pub fn custom_error(status: StatusCode, message: &str) -> Self {
+        ErrorResponse(
+            status,
+            ErrorMessage {
+                status: "fail",
+                message: String::from(message),
+            },
+        )
+    }
+    pub fn custom_error_message(message: &str) -> Self {
+        ErrorResponse(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorMessage {
+                status: "fail",
+                message: String::from(message),
+            },
+        )
+    }
+}
@@ -0,0 +1,10 @@
+use actix_web::{web, HttpResponse};
+
+#[tracing::instrument(name = "Ping")]
+async fn ping() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/ping", web::get().to(ping));
+}
@@ -0,0 +1,42 @@
+use crate::config::Settings;
+use crate::routes::routes as api_routes;
+use actix_cors::Cors;
+use actix_web::http::header::{ACCEPT, CONTENT_TYPE};
+use actix_web::{web, App, HttpServer};
+use anyhow::{Context, Result};
+use std::net::TcpListener;
+use tracing_actix_web::TracingLogger;
+
+pub async fn build(settings: Settings) -> Result<()> {
+    tracing::info!("Creating server...");
+
+    let origins = settings.application.origins.clone();
+
+    tracing::info!("Binding port {}...", settings.application.port);
+    let address = format!(
+        "{}:{}",
+        settings.application.host, settings.application.port
+    );
+    let listener = TcpListener::bind(address).context("Failed to bind to port")?;
+
+    tracing::info!("Running api...");
+    HttpServer::new(move || {
+        let cors = origins.iter().fold(
+            Cors::default()
+                .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
+                .allowed_headers(vec![ACCEPT, CONTENT_TYPE])
+                .supports_credentials(),
+            |cors, origin| cors.allowed_origin(origin),
+        );
+
+        App::new()
+            .service(web::scope("/api").configure(api_routes))
+            .wrap(cors)
+            .wrap(TracingLogger::default())
+    })
+    .listen(listener)
+    .context("Failed to listen on port")?
+    .run()
+    .await
+    .context("Failed to start server")
+}
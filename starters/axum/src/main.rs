@@ -1,10 +1,10 @@
-use api::config::get_configuration;
-use api::config::logging::{get_subscriber, init_subscriber};
-use api::startup::build;
+use {{ crate_name }}::config::get_configuration;
+use {{ crate_name }}::config::logging::{get_subscriber, init_subscriber};
+use {{ crate_name }}::startup::build;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let subscriber = get_subscriber("api".into(), "info".into(), std::io::stdout);
+    let subscriber = get_subscriber("{{ project_name }}".into(), "info".into(), std::io::stdout);
     init_subscriber(subscriber);
 
     let configuration = get_configuration().expect("Failed to read configuration.");
@@ -19,7 +19,13 @@ pub async fn build(settings: Settings) -> Result<()> {
         .collect();
 
     let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PUT])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
         .allow_origin(origins)
         .allow_credentials(true)
         .allow_headers([ACCEPT, CONTENT_TYPE]);